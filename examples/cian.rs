@@ -1,7 +1,7 @@
 use log::error;
 use scrapman::{
-    ClickElement, FillElement, FlowControl, JsonValue, OpenUrl, QueryElement, ScrapePipeline, ScrapeStage, Scrapman,
-    Selector, SetModelAttribute, StoreModel, Value,
+    ClickElement, FillElement, FlowControl, JsonValue, OpenUrl, QueryElement, ScrapeErrorKind, ScrapePipeline,
+    ScrapeStage, Scrapman, Selector, SetModelAttribute, StoreModel, Transform, Value, ValueType,
 };
 use std::{error::Error, fs::read_to_string};
 
@@ -33,11 +33,19 @@ async fn main() -> Result<(), Box<dyn Error>> {
                         // Title containing element is queried
                         .push(QueryElement::scoped(Selector::Css, Value::context("selectors.title")))
                         // Title element text value is stored in the context with "title" key
-                        .push(SetModelAttribute::new("title", Value::ElementText))
+                        .push(SetModelAttribute::new(
+                            "title",
+                            Value::ElementText.transform(Transform::CollapseWhitespace),
+                        ))
                         // Price containing element is queried
                         .push(QueryElement::scoped(Selector::Css, Value::context("selectors.price")))
-                        // Price element text value is stored in the context with "price" key
-                        .push(SetModelAttribute::new("price", Value::ElementText))
+                        // Price element text value is stored in the context with "price" key as a number
+                        .push(SetModelAttribute::new(
+                            "price",
+                            Value::ElementText
+                                .transform(Transform::StripNonDigits)
+                                .typed(ValueType::Number),
+                        ))
                         // All populated attributes are stored as a model and removed from the context
                         .push(StoreModel),
                 ),
@@ -56,11 +64,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .push(ScrapeStage::from(ClickElement))
         // Preload overlay element is queried with some interval, while it exists - the pipeline is not moving further
         // Once the loading is complete, and the overlay is removed from the page, the pipeline returns to the query cards stage
+        // Any other failure, as well as the overlay not disappearing in 30 seconds, stops the pipeline execution
         .push(
             ScrapeStage::from(QueryElement::global(Selector::Css, Value::context("selectors.preload")))
-                .on_complete(FlowControl::repeat_with_delay(0.5))
-                // TODO: error specific handlers
-                .on_any_error(FlowControl::goto("QueryCards")),
+                .on_complete(FlowControl::repeat_with_delay(0.5).with_max_attempts(60, FlowControl::Quit))
+                .on_error_kind(
+                    ScrapeErrorKind::ElementQueryEmptyResult,
+                    FlowControl::goto("QueryCards"),
+                )
+                .on_any_error(FlowControl::Quit),
         );
 
     let scrapman = Scrapman::new("http://localhost:4444");
@@ -0,0 +1,22 @@
+use log::error;
+use scrapman::{JsonValue, ScrapePipeline, Scrapman};
+use std::{error::Error, fs::read_to_string};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    pretty_env_logger::init_timed();
+
+    let values = serde_yaml::from_str::<JsonValue>(&read_to_string("data/cian.yaml")?)?;
+
+    // The pipeline is loaded from the definition file instead of being built in code
+    let pipeline = ScrapePipeline::from_path("data/cian_pipeline.yaml")?;
+
+    let scrapman = Scrapman::new("http://localhost:4444");
+
+    match scrapman.launch(pipeline, values).await {
+        Ok(ctx) => println!("{}", serde_json::to_string_pretty(&ctx.models)?),
+        Err(error) => error!("Error: {}", error),
+    };
+
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use crate::{
+    action::{ScrapeAction, ScrapeActionResult},
+    condition::Condition,
+    pipeline::{ScrapeContext, ScrapeError},
+    stage::FlowControl,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+/// Branches the pipeline execution on the condition: the "then" flow is applied if the condition is met, the "else"
+/// flow otherwise. The stage "on complete" flow is applied for the omitted branch
+#[derive(Debug, Serialize, Deserialize)]
+pub struct If {
+    pub condition: Condition,
+    #[serde(default)]
+    pub then: Option<FlowControl>,
+    #[serde(default, rename = "else")]
+    pub otherwise: Option<FlowControl>,
+}
+
+impl If {
+    pub fn new(condition: Condition) -> Self {
+        If {
+            condition,
+            then: None,
+            otherwise: None,
+        }
+    }
+
+    pub fn then(mut self, flow: FlowControl) -> Self {
+        self.then = Some(flow);
+        self
+    }
+
+    pub fn otherwise(mut self, flow: FlowControl) -> Self {
+        self.otherwise = Some(flow);
+        self
+    }
+}
+
+impl Display for If {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "check condition {}", self.condition)
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for If {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        self.condition.evaluate(context).await.map(|_| ())
+    }
+
+    async fn execute_with_flow<'a>(
+        &'a self,
+        context: &mut ScrapeContext,
+    ) -> Result<Option<&'a FlowControl>, ScrapeError> {
+        match self.condition.evaluate(context).await? {
+            true => Ok(self.then.as_ref()),
+            false => Ok(self.otherwise.as_ref()),
+        }
+    }
+
+    fn flows(&self) -> Vec<&FlowControl> {
+        self.then.iter().chain(self.otherwise.iter()).collect()
+    }
+}
+
+/// Fails with the `AssertionFailed` error kind and the message if the condition is not met
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Assert {
+    pub condition: Condition,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl Assert {
+    pub fn new(condition: Condition) -> Self {
+        Assert {
+            condition,
+            message: None,
+        }
+    }
+
+    pub fn with_message<T: Into<String>>(mut self, message: T) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+impl Display for Assert {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "assert condition {}", self.condition)
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for Assert {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        match self.condition.evaluate(context).await? {
+            true => Ok(()),
+            false => Err(ScrapeError::AssertionFailed(
+                self.message.clone().unwrap_or_else(|| self.condition.to_string()),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{If, ScrapeAction, SetModelAttribute, StoreModel},
+        client::MockScrapeClient,
+        condition::Condition,
+        pipeline::{ScrapeContext, ScrapePipeline},
+        stage::{FlowControl, ScrapeStage},
+        validation::ValidationError,
+        value::Value,
+    };
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_if_branching() {
+        let pipeline = ScrapePipeline::default()
+            .push(
+                ScrapeStage::from(
+                    If::new(Condition::contains(
+                        Value::context("price"),
+                        Value::constant("по запросу"),
+                    ))
+                    .then(FlowControl::Quit),
+                )
+                .on_any_error(FlowControl::Quit),
+            )
+            .push(SetModelAttribute::new("price", Value::context("price")))
+            .push(StoreModel);
+        assert_eq!(Ok(()), pipeline.validate());
+
+        let mut context = ScrapeContext::new(MockScrapeClient::new(), json!({"price": "10 000"}));
+        assert_eq!(true, pipeline.execute(&mut context).await.is_ok());
+        assert_eq!(vec![json!({"price": "10 000"})], context.models);
+
+        let mut context = ScrapeContext::new(MockScrapeClient::new(), json!({"price": "Цена по запросу"}));
+        assert_eq!(true, pipeline.execute(&mut context).await.is_ok());
+        assert_eq!(0, context.models.len());
+    }
+
+    #[test]
+    fn test_if_branch_validation() {
+        let pipeline: ScrapePipeline = serde_yaml::from_str(
+            r#"
+            stages:
+              - action:
+                  type: If
+                  condition:
+                    Exists: { Context: next_url }
+                  else: { Goto: Finish }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Err(vec![ValidationError::UnknownGotoTarget {
+                stage: "stages[0]".into(),
+                target: "Finish".into()
+            }]),
+            pipeline.validate()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deserialize_assert() {
+        let action: Box<dyn ScrapeAction> = serde_yaml::from_str(
+            r#"
+            type: Assert
+            condition:
+              Exists: { Context: url }
+            message: start URL is required
+            "#,
+        )
+        .unwrap();
+
+        let mut context = ScrapeContext::new(MockScrapeClient::new(), None);
+        let error = action.execute(&mut context).await.unwrap_err();
+        assert_eq!("assertion failed: start URL is required", error.to_string());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    action::{ScrapeAction, ScrapeActionResult},
+    action::{scroll::scroll_into_view, ScrapeAction, ScrapeActionResult},
     pipeline::{ScrapeContext, ScrapeError},
 };
 use async_trait::async_trait;
@@ -21,18 +21,58 @@ impl ScrapeAction for ClickElement {
     async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
         match context.current_element.take() {
             Some(mut element) => {
-                // Send keys action is required to scroll the element into the view
-                element
-                    .send_keys("")
-                    .await
-                    .map_err(ScrapeError::WebdriverCommandError)?;
+                // Static elements have no reference and are clicked as is
+                if let Some(reference) = element.reference() {
+                    scroll_into_view(context, reference).await?;
+                }
 
-                element.click().await.map_err(ScrapeError::WebdriverCommandError)?;
-
-                Ok(())
+                element.click().await
             }
 
             None => Err(ScrapeError::MissingElement),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{ClickElement, ScrapeAction},
+        client::MockScrapeClient,
+        element::MockScrapeElement,
+        pipeline::{ScrapeContext, ScrapeError},
+    };
+    use futures::future;
+    use mockall::Sequence;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_click_element() {
+        let mut sequence = Sequence::new();
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_execute()
+            .withf(|_, args| *args == vec![json!({"element": "button"})])
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Box::pin(future::ok(json!(null))));
+
+        let mut element = MockScrapeElement::new();
+        element
+            .expect_reference()
+            .returning(|| Some(json!({"element": "button"})));
+        element
+            .expect_click()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|| Box::pin(future::ok(())));
+
+        let mut context = ScrapeContext::new(client, None);
+        context.current_element = Some(Box::new(element));
+        assert_eq!(true, ClickElement.execute(&mut context).await.is_ok());
+
+        // Clicked element is not the current one anymore
+        let result = ClickElement.execute(&mut context).await;
+        assert_eq!(true, matches!(result, Err(ScrapeError::MissingElement)));
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{
+    action::{ScrapeAction, ScrapeActionResult},
+    pipeline::{ScrapeContext, ScrapeError},
+    value::Value,
+};
+use async_trait::async_trait;
+use json_dotpath::DotPaths;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ScriptArgument {
+    Value(Value),
+    CurrentElement,
+    ScopedElement,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ScriptResultTarget {
+    ModelAttribute(String),
+    ContextValue(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecuteScript {
+    pub script: String,
+    #[serde(default)]
+    pub arguments: Vec<ScriptArgument>,
+    #[serde(default)]
+    pub asynchronous: bool,
+    #[serde(default)]
+    pub result: Option<ScriptResultTarget>,
+}
+
+impl ExecuteScript {
+    pub fn new<T: Into<String>>(script: T) -> Self {
+        ExecuteScript {
+            script: script.into(),
+            arguments: Vec::new(),
+            asynchronous: false,
+            result: None,
+        }
+    }
+
+    /// Asynchronous script receives a completion callback as the last argument
+    pub fn new_async<T: Into<String>>(script: T) -> Self {
+        ExecuteScript {
+            asynchronous: true,
+            ..ExecuteScript::new(script)
+        }
+    }
+
+    pub fn with_argument(mut self, argument: ScriptArgument) -> Self {
+        self.arguments.push(argument);
+        self
+    }
+
+    pub fn store_in_model<T: Into<String>>(mut self, attribute: T) -> Self {
+        self.result = Some(ScriptResultTarget::ModelAttribute(attribute.into()));
+        self
+    }
+
+    pub fn store_in_context<T: Into<String>>(mut self, key: T) -> Self {
+        self.result = Some(ScriptResultTarget::ContextValue(key.into()));
+        self
+    }
+}
+
+impl Display for ExecuteScript {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        let mode = if self.asynchronous { "async" } else { "sync" };
+        match self.result {
+            Some(ref target) => write!(fmt, "execute {} script storing the result in {:?}", mode, target),
+            None => write!(fmt, "execute {} script", mode),
+        }
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for ExecuteScript {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        let mut arguments = Vec::with_capacity(self.arguments.len());
+        for argument in &self.arguments {
+            // Elements are passed to the script as webdriver element references
+            let element = match argument {
+                ScriptArgument::Value(value) => {
+                    arguments.push(value.resolve(context).await?);
+                    continue;
+                }
+                ScriptArgument::CurrentElement => context.current_element.as_ref(),
+                ScriptArgument::ScopedElement => context.scoped_element.as_ref(),
+            };
+
+            let element = element.ok_or(ScrapeError::MissingElement)?;
+            arguments.push(element.reference().ok_or(ScrapeError::MissingElement)?);
+        }
+
+        let result = if self.asynchronous {
+            context.client.execute_async(&self.script, arguments).await?
+        } else {
+            context.client.execute(&self.script, arguments).await?
+        };
+
+        match self.result {
+            Some(ScriptResultTarget::ModelAttribute(ref attribute)) => context
+                .model
+                .dot_set(attribute, result)
+                .map_err(|_| ScrapeError::SetModelAttributeError),
+
+            Some(ScriptResultTarget::ContextValue(ref key)) => context
+                .values
+                .dot_set(key, result)
+                .map_err(|_| ScrapeError::SetContextValueError),
+
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{ExecuteScript, ScrapeAction, ScriptArgument},
+        client::MockScrapeClient,
+        pipeline::{ScrapeContext, ScrapeError},
+        value::Value,
+    };
+    use futures::future;
+    use mockall::predicate;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_execute_script() {
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_execute()
+            .with(
+                predicate::eq("return arguments[0] * 2;"),
+                predicate::eq(vec![json!(21)]),
+            )
+            .times(1)
+            .returning(|_, _| Box::pin(future::ok(json!(42))));
+
+        let action = ExecuteScript::new("return arguments[0] * 2;")
+            .with_argument(ScriptArgument::Value(Value::constant(21)))
+            .store_in_model("answer");
+
+        let mut context = ScrapeContext::new(client, None);
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+        assert_eq!(json!({"answer": 42}), context.model);
+    }
+
+    #[tokio::test]
+    async fn test_execute_async_script() {
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_execute_async()
+            .times(1)
+            .returning(|_, _| Box::pin(future::ok(json!({"page": 2}))));
+
+        let action = ExecuteScript::new_async("arguments[0](window.__INITIAL_STATE__);").store_in_context("state");
+
+        let mut context = ScrapeContext::new(client, None);
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+        assert_eq!(json!({"state": {"page": 2}}), context.values);
+    }
+
+    #[tokio::test]
+    async fn test_missing_element_argument() {
+        let action = ExecuteScript::new("arguments[0].click();").with_argument(ScriptArgument::CurrentElement);
+
+        let mut context = ScrapeContext::new(MockScrapeClient::new(), None);
+        let result = action.execute(&mut context).await;
+        assert_eq!(true, matches!(result, Err(ScrapeError::MissingElement)));
+    }
+}
@@ -28,12 +28,9 @@ impl<'a> Display for FillElement {
 #[typetag::serde]
 impl ScrapeAction for FillElement {
     async fn execute(&self, mut context: &mut ScrapeContext) -> ScrapeActionResult {
-        let value = self.value.resolve(&mut context).await?.unwrap_or_default();
+        let value = self.value.resolve_string(&mut context).await?.unwrap_or_default();
         if let Some(ref mut element) = context.current_element {
-            element
-                .send_keys(&value)
-                .await
-                .map_err(ScrapeError::WebdriverCommandError)
+            element.send_keys(&value).await
         } else {
             Err(ScrapeError::MissingElement)
         }
@@ -1,26 +1,49 @@
+mod check_condition;
 mod click_element;
+mod execute_script;
 mod fill_element;
+mod mouse_interaction;
 mod open_url;
 mod pause;
+mod press_keys;
 mod query_element;
+mod scroll;
+mod select_option;
+mod set_context_value;
 mod set_model_attribute;
 mod store_model;
+mod take_screenshot;
+mod wait_for_element;
 
 #[cfg(test)]
 mod test;
 
+pub use check_condition::{Assert, If};
 pub use click_element::ClickElement;
+pub use execute_script::{ExecuteScript, ScriptArgument, ScriptResultTarget};
 pub use fill_element::FillElement;
+pub use mouse_interaction::{DoubleClickElement, DragElement, DragTarget, HoverElement, MouseInput, RightClickElement};
 pub use open_url::OpenUrl;
 pub use pause::Pause;
+pub use press_keys::{Key, PressKeys};
 pub use query_element::{ElementScope, QueryElement, Selector};
+pub use scroll::{Scroll, ScrollTarget, ScrollUntilLoaded};
+pub use select_option::{OptionSelector, SelectOption};
+pub use set_context_value::{IncrementContextValue, SetContextValue};
 pub use set_model_attribute::SetModelAttribute;
 pub use store_model::StoreModel;
+pub use take_screenshot::TakeScreenshot;
+pub use wait_for_element::{WaitCondition, WaitForElement};
+
+pub(crate) use take_screenshot::capture_failure;
 
 #[cfg(test)]
 pub use test::{TestError, TestSuccess};
 
-use crate::pipeline::{ScrapeContext, ScrapeError};
+use crate::{
+    pipeline::{ScrapeContext, ScrapeError, ScrapePipeline},
+    stage::FlowControl,
+};
 use async_trait::async_trait;
 use std::fmt::{Debug, Display};
 
@@ -30,4 +53,29 @@ pub type ScrapeActionResult = Result<(), ScrapeError>;
 #[typetag::serde(tag = "type")]
 pub trait ScrapeAction: Display + Send + Sync + Debug {
     async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult;
+
+    /// Action execution choosing the flow control applied instead of the stage "on complete" flow, the stage flow
+    /// control is applied if no flow is returned
+    async fn execute_with_flow<'a>(
+        &'a self,
+        context: &mut ScrapeContext,
+    ) -> Result<Option<&'a FlowControl>, ScrapeError> {
+        self.execute(context).await.map(|_| None)
+    }
+
+    /// Flow controls the action may choose on execution, used for the static pipeline validation
+    fn flows(&self) -> Vec<&FlowControl> {
+        Vec::new()
+    }
+
+    /// Delays in seconds the action waits for, used for the static pipeline validation
+    fn delays(&self) -> Vec<f64> {
+        Vec::new()
+    }
+
+    /// Nested pipeline executed by the action with the name of the action field holding it, used for the static
+    /// pipeline validation
+    fn nested_pipeline(&self) -> Option<(&'static str, &ScrapePipeline)> {
+        None
+    }
 }
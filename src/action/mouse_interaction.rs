@@ -0,0 +1,533 @@
+use crate::{
+    action::{
+        query_element::find_elements, scroll::scroll_into_view, ElementScope, ScrapeAction, ScrapeActionResult,
+        Selector,
+    },
+    pipeline::{ScrapeContext, ScrapeError},
+    value::{JsonValue, Value},
+};
+use async_trait::async_trait;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+// Mouse events are dispatched at the element center: arguments are the element and the list of [event type, button]
+const MOUSE_EVENTS_SCRIPT: &str = r#"
+    const [element, events] = arguments;
+    element.scrollIntoView({block: "center", inline: "center"});
+    const rect = element.getBoundingClientRect();
+    const x = rect.left + rect.width / 2, y = rect.top + rect.height / 2;
+    let detail = 0;
+    for (const [type, button] of events) {
+        if (type === "click" || type === "dblclick") detail = type === "dblclick" ? 2 : detail + 1;
+        const init = {bubbles: true, cancelable: true, view: window, clientX: x, clientY: y, button, detail,
+            buttons: type.endsWith("down") ? 1 << button : 0};
+        const event = type.startsWith("pointer") ? new PointerEvent(type, init) : new MouseEvent(type, init);
+        element.dispatchEvent(event);
+    }
+"#;
+
+// Both mouse and HTML5 drag and drop events are dispatched: arguments are the source element, the target element
+// or null, and the target offset relative to the source element center
+const DRAG_SCRIPT: &str = r#"
+    const [source, target, offset] = arguments;
+    source.scrollIntoView({block: "center", inline: "center"});
+    const center = (element) => {
+        const rect = element.getBoundingClientRect();
+        return [rect.left + rect.width / 2, rect.top + rect.height / 2];
+    };
+    const [sourceX, sourceY] = center(source);
+    const [targetX, targetY] = target ? center(target) : [sourceX + offset[0], sourceY + offset[1]];
+    const drop = target || document.elementFromPoint(targetX, targetY) || document.body;
+    const mouse = (element, type, x, y, buttons) => element.dispatchEvent(new MouseEvent(type,
+        {bubbles: true, cancelable: true, view: window, clientX: x, clientY: y, button: 0, buttons}));
+    const drag = (element, type, x, y, dataTransfer) => element.dispatchEvent(new DragEvent(type,
+        {bubbles: true, cancelable: true, view: window, clientX: x, clientY: y, dataTransfer}));
+
+    mouse(source, "mousedown", sourceX, sourceY, 1);
+    mouse(source, "mousemove", sourceX, sourceY, 1);
+    mouse(drop, "mousemove", targetX, targetY, 1);
+    mouse(drop, "mouseup", targetX, targetY, 0);
+    if (source.draggable) {
+        const dataTransfer = new DataTransfer();
+        drag(source, "dragstart", sourceX, sourceY, dataTransfer);
+        drag(drop, "dragenter", targetX, targetY, dataTransfer);
+        drag(drop, "dragover", targetX, targetY, dataTransfer);
+        drag(drop, "drop", targetX, targetY, dataTransfer);
+        drag(source, "dragend", targetX, targetY, dataTransfer);
+    }
+"#;
+
+const LEFT_BUTTON: u8 = 0;
+const RIGHT_BUTTON: u8 = 2;
+
+/// Pointer move duration in milliseconds while the button is held, the browser emits the intermediate moves
+const DRAG_DURATION: u64 = 200;
+
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Default)]
+pub enum MouseInput {
+    /// Trusted input performed by the browser with the WebDriver actions API. Script events are dispatched instead if
+    /// the client does not support the input actions
+    #[default]
+    WebDriver,
+    /// Synthetic events dispatched by the script, the events are not trusted (`isTrusted` is false) and may be
+    /// ignored by the page. HTML5 drag and drop is emulated only by this input in some browsers
+    ScriptFallback,
+}
+
+impl Display for MouseInput {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            MouseInput::WebDriver => write!(fmt, "WebDriver input"),
+            MouseInput::ScriptFallback => write!(fmt, "script events"),
+        }
+    }
+}
+
+/// Moves the mouse over the current element, e.g. to open a dropdown menu
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct HoverElement {
+    #[serde(default)]
+    pub input: MouseInput,
+}
+
+impl HoverElement {
+    pub fn new() -> Self {
+        HoverElement::default()
+    }
+
+    pub fn with_script_fallback(mut self) -> Self {
+        self.input = MouseInput::ScriptFallback;
+        self
+    }
+}
+
+impl Display for HoverElement {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "hover the current element with {}", self.input)
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for HoverElement {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        if self.input == MouseInput::WebDriver {
+            let element = current_reference(context)?;
+            if let Some(result) = perform(context, element.clone(), vec![pointer_move(element, 0, 0, 0)]).await {
+                return result;
+            }
+        }
+
+        let events = [
+            "pointerover",
+            "pointerenter",
+            "mouseover",
+            "mouseenter",
+            "pointermove",
+            "mousemove",
+        ];
+        dispatch(context, events.iter().map(|event| (*event, LEFT_BUTTON))).await
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct DoubleClickElement {
+    #[serde(default)]
+    pub input: MouseInput,
+}
+
+impl DoubleClickElement {
+    pub fn new() -> Self {
+        DoubleClickElement::default()
+    }
+
+    pub fn with_script_fallback(mut self) -> Self {
+        self.input = MouseInput::ScriptFallback;
+        self
+    }
+}
+
+impl Display for DoubleClickElement {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "double click the current element with {}", self.input)
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for DoubleClickElement {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        if self.input == MouseInput::WebDriver {
+            let element = current_reference(context)?;
+            let actions = vec![
+                pointer_move(element.clone(), 0, 0, 0),
+                pointer_button("pointerDown", LEFT_BUTTON),
+                pointer_button("pointerUp", LEFT_BUTTON),
+                pointer_button("pointerDown", LEFT_BUTTON),
+                pointer_button("pointerUp", LEFT_BUTTON),
+            ];
+            if let Some(result) = perform(context, element, actions).await {
+                return result;
+            }
+        }
+
+        let events = [
+            "mousedown",
+            "mouseup",
+            "click",
+            "mousedown",
+            "mouseup",
+            "click",
+            "dblclick",
+        ];
+        dispatch(context, events.iter().map(|event| (*event, LEFT_BUTTON))).await
+    }
+}
+
+/// Opens the context menu of the current element
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RightClickElement {
+    #[serde(default)]
+    pub input: MouseInput,
+}
+
+impl RightClickElement {
+    pub fn new() -> Self {
+        RightClickElement::default()
+    }
+
+    pub fn with_script_fallback(mut self) -> Self {
+        self.input = MouseInput::ScriptFallback;
+        self
+    }
+}
+
+impl Display for RightClickElement {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "right click the current element with {}", self.input)
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for RightClickElement {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        if self.input == MouseInput::WebDriver {
+            let element = current_reference(context)?;
+            let actions = vec![
+                pointer_move(element.clone(), 0, 0, 0),
+                pointer_button("pointerDown", RIGHT_BUTTON),
+                pointer_button("pointerUp", RIGHT_BUTTON),
+            ];
+            if let Some(result) = perform(context, element, actions).await {
+                return result;
+            }
+        }
+
+        let events = ["mousedown", "mouseup", "contextmenu"];
+        dispatch(context, events.iter().map(|event| (*event, RIGHT_BUTTON))).await
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum DragTarget {
+    /// Offset in pixels relative to the current element center, e.g. to move a slider handle
+    Offset { x: i64, y: i64 },
+    /// The first element matching the query
+    Element {
+        selector: Selector,
+        query: Value,
+        scope: ElementScope,
+    },
+}
+
+/// Drags the current element and drops it onto the target. The pointer is moved with the button held, pages
+/// relying on the HTML5 drag and drop events may require the script fallback input
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DragElement {
+    pub target: DragTarget,
+    #[serde(default)]
+    pub input: MouseInput,
+}
+
+impl DragElement {
+    pub fn by_offset(x: i64, y: i64) -> Self {
+        DragElement {
+            target: DragTarget::Offset { x, y },
+            input: MouseInput::default(),
+        }
+    }
+
+    pub fn to_element(selector: Selector, query: Value, scope: ElementScope) -> Self {
+        DragElement {
+            target: DragTarget::Element { selector, query, scope },
+            input: MouseInput::default(),
+        }
+    }
+
+    pub fn with_script_fallback(mut self) -> Self {
+        self.input = MouseInput::ScriptFallback;
+        self
+    }
+}
+
+impl Display for DragElement {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        match self.target {
+            DragTarget::Offset { x, y } => {
+                write!(fmt, "drag the current element by ({}, {}) with {}", x, y, self.input)
+            }
+            DragTarget::Element {
+                selector,
+                ref query,
+                scope,
+            } => write!(
+                fmt,
+                "drag the current element to {:?} element by {:?} selector {} with {}",
+                scope, selector, query, self.input
+            ),
+        }
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for DragElement {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        // Target element reference, or none for the offset relative to the source element center
+        let (target, x, y) = match self.target {
+            DragTarget::Offset { x, y } => (None, x, y),
+            DragTarget::Element {
+                selector,
+                ref query,
+                scope,
+            } => {
+                let query = query.resolve_string(context).await?.ok_or(ScrapeError::MissingQuery)?;
+                let target = find_elements(context, selector, &query, scope)
+                    .await?
+                    .into_iter()
+                    .next()
+                    .ok_or(ScrapeError::ElementQueryEmptyResult)?;
+
+                (Some(target.reference().ok_or(ScrapeError::MissingElement)?), 0, 0)
+            }
+        };
+
+        let source = current_reference(context)?;
+        if self.input == MouseInput::WebDriver {
+            // Offset is relative to the pointer, which is at the source element center after the first move
+            let origin = target.clone().unwrap_or_else(|| json!("pointer"));
+            let actions = vec![
+                pointer_move(source.clone(), 0, 0, 0),
+                pointer_button("pointerDown", LEFT_BUTTON),
+                pointer_move(origin, x, y, DRAG_DURATION),
+                pointer_button("pointerUp", LEFT_BUTTON),
+            ];
+            if let Some(result) = perform(context, source.clone(), actions).await {
+                return result;
+            }
+        }
+
+        let arguments = vec![source, target.unwrap_or_default(), json!([x, y])];
+        context.client.execute(DRAG_SCRIPT, arguments).await.map(|_| ())
+    }
+}
+
+/// Script argument referencing the current element
+fn current_reference(context: &ScrapeContext) -> Result<JsonValue, ScrapeError> {
+    context
+        .current_element
+        .as_ref()
+        .and_then(|element| element.reference())
+        .ok_or(ScrapeError::MissingElement)
+}
+
+/// Pointer move to the offset from the origin: the element center, or the current pointer position
+fn pointer_move(origin: JsonValue, x: i64, y: i64, duration: u64) -> JsonValue {
+    json!({"type": "pointerMove", "origin": origin, "x": x, "y": y, "duration": duration})
+}
+
+fn pointer_button(action: &str, button: u8) -> JsonValue {
+    json!({"type": action, "button": button})
+}
+
+/// The element is scrolled into view first, pointer moves outside of the viewport are rejected by WebDriver. Returns
+/// none if the client does not support the input actions, so the script events are dispatched instead
+async fn perform(
+    context: &mut ScrapeContext,
+    element: JsonValue,
+    actions: Vec<JsonValue>,
+) -> Option<ScrapeActionResult> {
+    if let Err(error) = scroll_into_view(context, element).await {
+        return Some(Err(error));
+    }
+
+    let mouse = json!({
+        "type": "pointer",
+        "id": "mouse",
+        "parameters": {"pointerType": "mouse"},
+        "actions": actions,
+    });
+
+    match context.client.perform_actions(vec![mouse]).await {
+        Err(ScrapeError::UnsupportedOperation(error)) => {
+            info!("{}, dispatching script events instead", error);
+            None
+        }
+        result => Some(result),
+    }
+}
+
+async fn dispatch<'a, I>(context: &mut ScrapeContext, events: I) -> ScrapeActionResult
+where
+    I: Iterator<Item = (&'a str, u8)>,
+{
+    let element = current_reference(context)?;
+    let events: Vec<_> = events.map(|(event, button)| json!([event, button])).collect();
+    context
+        .client
+        .execute(MOUSE_EVENTS_SCRIPT, vec![element, JsonValue::Array(events)])
+        .await
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{DragElement, ElementScope, RightClickElement, ScrapeAction, Selector},
+        client::MockScrapeClient,
+        element::{Locator, MockScrapeElement, ScrapeElement},
+        pipeline::{ScrapeContext, ScrapeError},
+        value::Value,
+    };
+    use futures::future;
+    use mockall::Sequence;
+    use serde_json::json;
+
+    fn element(id: &'static str) -> Box<dyn ScrapeElement> {
+        let mut element = MockScrapeElement::new();
+        element
+            .expect_reference()
+            .returning(move || Some(json!({ "element": id })));
+        Box::new(element)
+    }
+
+    #[tokio::test]
+    async fn test_right_click() {
+        let mut sequence = Sequence::new();
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_execute()
+            .withf(|_, args| *args == vec![json!({"element": "menu"})])
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Box::pin(future::ok(json!(null))));
+        client
+            .expect_perform_actions()
+            .withf(|actions| {
+                actions[0]["actions"]
+                    == json!([
+                        {"type": "pointerMove", "origin": {"element": "menu"}, "x": 0, "y": 0, "duration": 0},
+                        {"type": "pointerDown", "button": 2},
+                        {"type": "pointerUp", "button": 2},
+                    ])
+            })
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_| Box::pin(future::ok(())));
+
+        let mut context = ScrapeContext::new(client, None);
+        context.current_element = Some(element("menu"));
+        assert_eq!(true, RightClickElement::new().execute(&mut context).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_right_click_script_fallback() {
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_execute()
+            .withf(|_, args| {
+                args[0] == json!({"element": "menu"})
+                    && args[1] == json!([["mousedown", 2], ["mouseup", 2], ["contextmenu", 2]])
+            })
+            .times(1)
+            .returning(|_, _| Box::pin(future::ok(json!(null))));
+
+        let mut context = ScrapeContext::new(client, None);
+        context.current_element = Some(element("menu"));
+        let action: RightClickElement = serde_yaml::from_str("input: ScriptFallback").unwrap();
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_actions_fallback() {
+        let mut sequence = Sequence::new();
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_execute()
+            .withf(|_, args| *args == vec![json!({"element": "menu"})])
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Box::pin(future::ok(json!(null))));
+        client
+            .expect_perform_actions()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_| Box::pin(future::err(ScrapeError::UnsupportedOperation("no actions".to_owned()))));
+        client
+            .expect_execute()
+            .withf(|_, args| args[1] == json!([["mousedown", 2], ["mouseup", 2], ["contextmenu", 2]]))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Box::pin(future::ok(json!(null))));
+
+        let mut context = ScrapeContext::new(client, None);
+        context.current_element = Some(element("menu"));
+        assert_eq!(true, RightClickElement::new().execute(&mut context).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_drag_to_element() {
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_find_all()
+            .withf(|locator| *locator == Locator::Id("basket"))
+            .times(1)
+            .returning(|_| Box::pin(future::ok(vec![element("basket")])));
+        client
+            .expect_execute()
+            .withf(|_, args| *args == vec![json!({"element": "card"})])
+            .times(1)
+            .returning(|_, _| Box::pin(future::ok(json!(null))));
+        client
+            .expect_perform_actions()
+            .withf(|actions| {
+                actions[0]["actions"][0]["origin"] == json!({"element": "card"})
+                    && actions[0]["actions"][2]["origin"] == json!({"element": "basket"})
+            })
+            .times(1)
+            .returning(|_| Box::pin(future::ok(())));
+
+        let mut context = ScrapeContext::new(client, None);
+        context.current_element = Some(element("card"));
+        let action = DragElement::to_element(Selector::Id, Value::constant("basket"), ElementScope::Global);
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_drag_by_offset_script_fallback() {
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_execute()
+            .withf(|_, args| *args == vec![json!({"element": "handle"}), json!(null), json!([40, 0])])
+            .times(1)
+            .returning(|_, _| Box::pin(future::ok(json!(null))));
+
+        let mut context = ScrapeContext::new(client, None);
+        context.current_element = Some(element("handle"));
+        let action = DragElement::by_offset(40, 0).with_script_fallback();
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+    }
+}
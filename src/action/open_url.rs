@@ -29,7 +29,7 @@ impl Display for OpenUrl {
 #[typetag::serde]
 impl ScrapeAction for OpenUrl {
     async fn execute(&self, mut context: &mut ScrapeContext) -> ScrapeActionResult {
-        match self.url.resolve(&mut context).await? {
+        match self.url.resolve_string(&mut context).await? {
             Some(url) => context.client.goto(&url).await,
             None => Err(ScrapeError::MissingUrl),
         }
@@ -0,0 +1,141 @@
+use crate::{
+    action::{ScrapeAction, ScrapeActionResult},
+    pipeline::{ScrapeContext, ScrapeError},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+/// Releases the modifier keys pressed within the keys sequence
+const NULL_KEY: char = '\u{E000}';
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum Key {
+    Enter,
+    Tab,
+    Escape,
+    Space,
+    Backspace,
+    Delete,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Shift,
+    Control,
+    Alt,
+    Meta,
+    /// Printable characters typed as is
+    Text(String),
+}
+
+impl Key {
+    /// Code point of the key defined by the WebDriver specification
+    fn code(&self) -> Option<char> {
+        match self {
+            Key::Enter => Some('\u{E007}'),
+            Key::Tab => Some('\u{E004}'),
+            Key::Escape => Some('\u{E00C}'),
+            Key::Space => Some('\u{E00D}'),
+            Key::Backspace => Some('\u{E003}'),
+            Key::Delete => Some('\u{E017}'),
+            Key::ArrowUp => Some('\u{E013}'),
+            Key::ArrowDown => Some('\u{E015}'),
+            Key::ArrowLeft => Some('\u{E012}'),
+            Key::ArrowRight => Some('\u{E014}'),
+            Key::Home => Some('\u{E011}'),
+            Key::End => Some('\u{E010}'),
+            Key::PageUp => Some('\u{E00E}'),
+            Key::PageDown => Some('\u{E00F}'),
+            Key::Shift => Some('\u{E008}'),
+            Key::Control => Some('\u{E009}'),
+            Key::Alt => Some('\u{E00A}'),
+            Key::Meta => Some('\u{E03D}'),
+            Key::Text(_) => None,
+        }
+    }
+}
+
+/// Presses the keys one after another on the current element. Modifier keys are held until the end of the sequence,
+/// so `[Control, Text("a")]` is pressed as a chord
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PressKeys {
+    pub keys: Vec<Key>,
+}
+
+impl PressKeys {
+    pub fn new(keys: Vec<Key>) -> Self {
+        PressKeys { keys }
+    }
+
+    pub fn key(key: Key) -> Self {
+        PressKeys::new(vec![key])
+    }
+
+    /// WebDriver send keys sequence, modifier keys are released at the end
+    fn sequence(&self) -> String {
+        let mut sequence = String::new();
+        for key in &self.keys {
+            match key {
+                Key::Text(text) => sequence.push_str(text),
+                key => sequence.extend(key.code()),
+            }
+        }
+
+        sequence.push(NULL_KEY);
+        sequence
+    }
+}
+
+impl Display for PressKeys {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "press keys {:?} on the current element", self.keys)
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for PressKeys {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        match context.current_element {
+            Some(ref mut element) => element.send_keys(&self.sequence()).await,
+            None => Err(ScrapeError::MissingElement),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{Key, PressKeys, ScrapeAction},
+        client::MockScrapeClient,
+        element::MockScrapeElement,
+        pipeline::ScrapeContext,
+    };
+    use futures::future;
+    use mockall::predicate;
+
+    #[tokio::test]
+    async fn test_press_keys() {
+        let mut element = MockScrapeElement::new();
+        element
+            .expect_send_keys()
+            .with(predicate::eq("\u{E015}\u{E007}\u{E009}a\u{E000}"))
+            .times(1)
+            .returning(|_| Box::pin(future::ok(())));
+
+        let mut context = ScrapeContext::new(MockScrapeClient::new(), None);
+        context.current_element = Some(Box::new(element));
+
+        let action: PressKeys = serde_yaml::from_str("keys: [ArrowDown, Enter, Control, { Text: a }]").unwrap();
+        assert_eq!(
+            vec![Key::ArrowDown, Key::Enter, Key::Control, Key::Text("a".into())],
+            action.keys
+        );
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+    }
+}
@@ -1,31 +1,72 @@
 use crate::{
     action::{ScrapeAction, ScrapeActionResult},
+    element::{Locator, ScrapeElement},
     pipeline::{ScrapeContext, ScrapeError, ScrapePipeline},
     value::Value,
 };
 use async_trait::async_trait;
-use fantoccini::{elements::Element, Locator};
 use log::error;
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter, Result as FormatResult};
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter, Result as FormatResult},
+};
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
 pub enum Selector {
     Css,
     Id,
     LinkText,
+    PartialLinkText,
+    XPath,
+    TagName,
+    Name,
 }
 
 impl Selector {
+    /// Locator for the query prepared with `Selector::format_query`
     pub fn get_locator<'a>(&'a self, query: &'a str) -> Locator<'a> {
         match self {
-            Selector::Css => Locator::Css(query),
+            Selector::Css | Selector::TagName | Selector::Name => Locator::Css(query),
             Selector::Id => Locator::Id(query),
             Selector::LinkText => Locator::LinkText(query),
+            Selector::XPath | Selector::PartialLinkText => Locator::XPath(query),
+        }
+    }
+
+    /// Strategies not supported by the webdriver client natively are expressed with CSS or XPath queries
+    pub fn format_query<'a>(&self, query: &'a str) -> Cow<'a, str> {
+        match self {
+            Selector::Name => Cow::Owned(format!("[name=\"{}\"]", escape_css_string(query))),
+            Selector::PartialLinkText => {
+                // Relative path keeps the scoped and current element queries within the element subtree
+                Cow::Owned(format!(".//a[contains(., {})]", xpath_literal(query)))
+            }
+            _ => Cow::Borrowed(query),
         }
     }
 }
 
+fn escape_css_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn xpath_literal(value: &str) -> String {
+    if !value.contains('"') {
+        format!("\"{}\"", value)
+    } else if !value.contains('\'') {
+        format!("'{}'", value)
+    } else {
+        // XPath 1.0 has no escape sequences, literals containing both quote types are concatenated
+        let parts = value
+            .split('"')
+            .map(|part| format!("\"{}\"", part))
+            .collect::<Vec<_>>()
+            .join(", '\"', ");
+        format!("concat({})", parts)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
 pub enum ElementScope {
     Global,
@@ -85,17 +126,11 @@ impl ScrapeAction for QueryElement {
     async fn execute(&self, mut context: &mut ScrapeContext) -> ScrapeActionResult {
         let query = self
             .query
-            .resolve(&mut context)
+            .resolve_string(&mut context)
             .await?
             .ok_or(ScrapeError::MissingQuery)?;
 
-        let locator = self.selector.get_locator(&query);
-        let mut elements = match self.scope {
-            ElementScope::Global => context.client.find_all(locator).await?,
-            ElementScope::Scoped => find_child_elements(&mut context.scoped_element, locator).await?,
-            ElementScope::Current => find_child_elements(&mut context.current_element, locator).await?,
-        };
-
+        let mut elements = find_elements(&mut context, self.selector, &query, self.scope).await?;
         if elements.is_empty() {
             return Err(ScrapeError::ElementQueryEmptyResult);
         }
@@ -109,9 +144,14 @@ impl ScrapeAction for QueryElement {
                 context.scoped_element = Some(element.clone());
                 context.current_element = Some(element.clone());
 
-                // Nested pipeline execution launch
-                if let Err(error) = pipeline.execute(&mut context).await {
-                    error!("Nested pipeline execution error: {}", error);
+                // Nested pipeline execution launch, fatal errors stop the enclosing pipeline as well
+                match pipeline.execute(&mut context).await {
+                    Err(error) if error.is_fatal() => {
+                        context.scoped_element = current_scoped;
+                        return Err(error);
+                    }
+                    Err(error) => error!("Nested pipeline execution error: {}", error),
+                    Ok(_) => (),
                 }
 
                 // Current element set to the last element in the sequence
@@ -128,18 +168,154 @@ impl ScrapeAction for QueryElement {
 
         Ok(())
     }
+
+    fn nested_pipeline(&self) -> Option<(&'static str, &ScrapePipeline)> {
+        self.for_each.as_ref().map(|pipeline| ("for_each", pipeline))
+    }
+}
+
+/// Elements matching the query in the specified scope of the pipeline execution context
+pub(crate) async fn find_elements(
+    context: &mut ScrapeContext,
+    selector: Selector,
+    query: &str,
+    scope: ElementScope,
+) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+    let query = selector.format_query(query);
+    let locator = selector.get_locator(&query);
+    match scope {
+        ElementScope::Global => context.client.find_all(locator).await,
+        ElementScope::Scoped => find_child_elements(&mut context.scoped_element, locator).await,
+        ElementScope::Current => find_child_elements(&mut context.current_element, locator).await,
+    }
 }
 
 async fn find_child_elements<'a>(
-    element: &mut Option<Element>,
+    element: &mut Option<Box<dyn ScrapeElement>>,
     locator: Locator<'a>,
-) -> Result<Vec<Element>, ScrapeError> {
+) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
     if let Some(element) = element {
-        element
-            .find_all(locator)
-            .await
-            .map_err(ScrapeError::WebdriverCommandError)
+        element.find_all(locator).await
     } else {
         Err(ScrapeError::MissingElement)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Selector;
+    use crate::{
+        action::{QueryElement, ScrapeAction, SetModelAttribute, StoreModel},
+        client::MockScrapeClient,
+        element::{mock_element, Locator, MockScrapeElement},
+        pipeline::{ScrapeContext, ScrapeError, ScrapePipeline},
+        sink::ChannelSink,
+        value::Value,
+    };
+    use futures::future;
+    use serde_json::json;
+
+    #[test]
+    fn test_native_query() {
+        assert_eq!("div > a", Selector::Css.format_query("div > a"));
+        assert_eq!("//div[@class]", Selector::XPath.format_query("//div[@class]"));
+        assert_eq!("span", Selector::TagName.format_query("span"));
+    }
+
+    #[test]
+    fn test_name_query() {
+        assert_eq!("[name=\"q\"]", Selector::Name.format_query("q"));
+        assert_eq!("[name=\"a\\\"b\"]", Selector::Name.format_query("a\"b"));
+    }
+
+    #[test]
+    fn test_partial_link_text_query() {
+        assert_eq!(
+            ".//a[contains(., \"Next\")]",
+            Selector::PartialLinkText.format_query("Next")
+        );
+        assert_eq!(
+            ".//a[contains(., 'say \"hi\"')]",
+            Selector::PartialLinkText.format_query("say \"hi\"")
+        );
+        assert_eq!(
+            ".//a[contains(., concat(\"it's \", '\"', \"x\", '\"', \"\"))]",
+            Selector::PartialLinkText.format_query("it's \"x\"")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scoped_partial_link_text_query() {
+        let mut scope = MockScrapeElement::new();
+        scope
+            .expect_find_all()
+            .withf(|locator| *locator == Locator::XPath(".//a[contains(., \"Next\")]"))
+            .times(1)
+            .returning(|_| Box::pin(future::ok(vec![mock_element("Next page")])));
+
+        let mut context = ScrapeContext::new(MockScrapeClient::new(), None);
+        context.scoped_element = Some(Box::new(scope));
+
+        let action = QueryElement::scoped(Selector::PartialLinkText, Value::constant("Next"));
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+        assert_eq!(true, context.current_element.is_some());
+    }
+
+    #[test]
+    fn test_deserialize_selector() {
+        let selectors: Vec<Selector> =
+            serde_json::from_str(r#"["XPath", "PartialLinkText", "TagName", "Name"]"#).unwrap();
+        assert_eq!(4, selectors.len());
+    }
+
+    #[tokio::test]
+    async fn test_for_each_sink_error() {
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_find_all()
+            .times(1)
+            .returning(|_| Box::pin(future::ok(vec![mock_element("First"), mock_element("Second")])));
+
+        // Closed channel receiver fails the model output of the first element
+        let (sink, receiver) = ChannelSink::new(1);
+        drop(receiver);
+
+        let action = QueryElement::global(Selector::Css, Value::constant("article")).for_each(
+            ScrapePipeline::default()
+                .push(SetModelAttribute::new("title", Value::ElementText))
+                .push(StoreModel),
+        );
+
+        let mut context = ScrapeContext::new(client, None).with_sink(sink);
+        assert_eq!(
+            true,
+            matches!(action.execute(&mut context).await, Err(ScrapeError::ModelSinkError(_)))
+        );
+        assert_eq!(true, context.scoped_element.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_for_each_element() {
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_find_all()
+            .withf(|locator| *locator == Locator::Css("[name=\"card\"]"))
+            .times(1)
+            .returning(|_| Box::pin(future::ok(vec![mock_element("First"), mock_element("Second")])));
+
+        let action = QueryElement::global(Selector::Name, Value::constant("card")).for_each(
+            ScrapePipeline::default()
+                .push(SetModelAttribute::new("title", Value::ElementText))
+                .push(StoreModel),
+        );
+
+        let mut context = ScrapeContext::new(client, None);
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+        assert_eq!(
+            vec![json!({"title": "First"}), json!({"title": "Second"})],
+            context.models
+        );
+        assert_eq!(true, context.scoped_element.is_none());
+        assert_eq!(true, context.current_element.is_some());
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{
+    action::{query_element::find_elements, ElementScope, ScrapeAction, ScrapeActionResult, Selector},
+    pipeline::{ScrapeContext, ScrapeError},
+    stage::clamp_delay,
+    value::{JsonValue, Value},
+};
+use async_trait::async_trait;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+use tokio::time::{sleep, Duration};
+
+const INTO_VIEW_SCRIPT: &str = r#"arguments[0].scrollIntoView({block: "center", inline: "center"});"#;
+
+const SCROLL_BY_SCRIPT: &str = "window.scrollBy(arguments[0], arguments[1]);";
+
+const TO_BOTTOM_SCRIPT: &str = "window.scrollTo(0, document.scrollingElement.scrollHeight);";
+
+// The last loaded element is scrolled into view to trigger the lazy loading observers, then the container element
+// or the page is scrolled to the bottom: arguments are the container element or null, and the last element or null
+const LOAD_MORE_SCRIPT: &str = r#"
+    const [container, last] = arguments;
+    if (last) {
+        last.scrollIntoView({block: "end"});
+    }
+
+    if (container) {
+        container.scrollTop = container.scrollHeight;
+    } else {
+        window.scrollTo(0, document.scrollingElement.scrollHeight);
+    }
+"#;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ScrollTarget {
+    /// The first element matching the query, or the current element without the query, is scrolled to the center of
+    /// the viewport. Global scope is used by default
+    IntoView {
+        #[serde(default)]
+        selector: Option<Selector>,
+        #[serde(default)]
+        query: Option<Value>,
+        #[serde(default)]
+        scope: Option<ElementScope>,
+    },
+    /// Page is scrolled by the offset in pixels
+    By { x: i64, y: i64 },
+    /// Page is scrolled to the bottom
+    ToBottom,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Scroll {
+    pub target: ScrollTarget,
+}
+
+impl Scroll {
+    pub fn into_view() -> Self {
+        Scroll {
+            target: ScrollTarget::IntoView {
+                selector: None,
+                query: None,
+                scope: None,
+            },
+        }
+    }
+
+    pub fn element_into_view(selector: Selector, query: Value, scope: ElementScope) -> Self {
+        Scroll {
+            target: ScrollTarget::IntoView {
+                selector: Some(selector),
+                query: Some(query),
+                scope: Some(scope),
+            },
+        }
+    }
+
+    pub fn by(x: i64, y: i64) -> Self {
+        Scroll {
+            target: ScrollTarget::By { x, y },
+        }
+    }
+
+    pub fn to_bottom() -> Self {
+        Scroll {
+            target: ScrollTarget::ToBottom,
+        }
+    }
+}
+
+impl Display for Scroll {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        match self.target {
+            ScrollTarget::IntoView {
+                selector: Some(selector),
+                query: Some(ref query),
+                scope,
+            } => write!(
+                fmt,
+                "scroll {:?} element by {:?} selector {} into view",
+                scope.unwrap_or(ElementScope::Global),
+                selector,
+                query
+            ),
+            ScrollTarget::IntoView { .. } => write!(fmt, "scroll the current element into view"),
+            ScrollTarget::By { x, y } => write!(fmt, "scroll the page by ({}, {})", x, y),
+            ScrollTarget::ToBottom => write!(fmt, "scroll the page to the bottom"),
+        }
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for Scroll {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        let (script, arguments) = match self.target {
+            ScrollTarget::IntoView {
+                selector,
+                ref query,
+                scope,
+            } => {
+                let element = match (selector, query) {
+                    (None, None) => context.current_element.as_ref().and_then(|element| element.reference()),
+                    (Some(selector), Some(query)) => {
+                        let query = query.resolve_string(context).await?.ok_or(ScrapeError::MissingQuery)?;
+                        let scope = scope.unwrap_or(ElementScope::Global);
+                        find_elements(context, selector, &query, scope)
+                            .await?
+                            .into_iter()
+                            .next()
+                            .ok_or(ScrapeError::ElementQueryEmptyResult)?
+                            .reference()
+                    }
+                    _ => return Err(ScrapeError::MissingQuery),
+                };
+
+                (INTO_VIEW_SCRIPT, vec![element.ok_or(ScrapeError::MissingElement)?])
+            }
+
+            ScrollTarget::By { x, y } => (SCROLL_BY_SCRIPT, vec![json!(x), json!(y)]),
+            ScrollTarget::ToBottom => (TO_BOTTOM_SCRIPT, Vec::new()),
+        };
+
+        context.client.execute(script, arguments).await.map(|_| ())
+    }
+}
+
+/// Scrolls the referenced element to the center of the viewport
+pub(crate) async fn scroll_into_view(context: &mut ScrapeContext, element: JsonValue) -> ScrapeActionResult {
+    context
+        .client
+        .execute(INTO_VIEW_SCRIPT, vec![element])
+        .await
+        .map(|_| ())
+}
+
+/// Infinite feed loading: the page, or the scope element for the scoped queries, is scrolled until no new elements
+/// matching the query appear after the delay, or the scrolls limit is reached
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScrollUntilLoaded {
+    pub selector: Selector,
+    pub query: Value,
+    pub scope: ElementScope,
+    #[serde(default = "default_max_scrolls")]
+    pub max_scrolls: u32,
+    /// Seconds to wait for the new elements after every scroll
+    #[serde(default = "default_delay")]
+    pub delay: f64,
+}
+
+fn default_max_scrolls() -> u32 {
+    50
+}
+
+fn default_delay() -> f64 {
+    1.0
+}
+
+impl ScrollUntilLoaded {
+    pub fn new(selector: Selector, query: Value, scope: ElementScope) -> Self {
+        ScrollUntilLoaded {
+            selector,
+            query,
+            scope,
+            max_scrolls: default_max_scrolls(),
+            delay: default_delay(),
+        }
+    }
+
+    pub fn global(selector: Selector, query: Value) -> Self {
+        ScrollUntilLoaded::new(selector, query, ElementScope::Global)
+    }
+
+    pub fn with_max_scrolls(mut self, max_scrolls: u32) -> Self {
+        self.max_scrolls = max_scrolls;
+        self
+    }
+
+    /// Seconds to wait for the new elements after every scroll
+    pub fn with_delay(mut self, delay: f64) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+impl Display for ScrollUntilLoaded {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(
+            fmt,
+            "scroll up to {} times until no new elements with {:?} selector in {:?} scope with the query value from {} appear",
+            self.max_scrolls, self.selector, self.scope, self.query
+        )
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for ScrollUntilLoaded {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        let query = self
+            .query
+            .resolve_string(context)
+            .await?
+            .ok_or(ScrapeError::MissingQuery)?;
+
+        let container = match self.scope {
+            ElementScope::Global => None,
+            ElementScope::Scoped => context.scoped_element.as_ref(),
+            ElementScope::Current => context.current_element.as_ref(),
+        };
+        let container = container.and_then(|element| element.reference()).unwrap_or_default();
+
+        let mut elements = find_elements(context, self.selector, &query, self.scope).await?;
+        for scroll in 1..=self.max_scrolls {
+            let last = elements
+                .last()
+                .and_then(|element| element.reference())
+                .unwrap_or_default();
+            context
+                .client
+                .execute(LOAD_MORE_SCRIPT, vec![container.clone(), last])
+                .await?;
+
+            sleep(Duration::from_secs_f64(clamp_delay(self.delay))).await;
+            let loaded = find_elements(context, self.selector, &query, self.scope).await?;
+            if loaded.len() <= elements.len() {
+                info!(
+                    "No new elements after {} scrolls, {} elements loaded",
+                    scroll,
+                    loaded.len()
+                );
+                return Ok(());
+            }
+
+            elements = loaded;
+        }
+
+        info!("Scrolls limit reached, {} elements loaded", elements.len());
+        Ok(())
+    }
+
+    fn delays(&self) -> Vec<f64> {
+        vec![self.delay]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{ElementScope, ScrapeAction, Scroll, ScrollUntilLoaded, Selector},
+        client::MockScrapeClient,
+        element::{mock_element, Locator, MockScrapeElement, ScrapeElement},
+        pipeline::ScrapeContext,
+        value::Value,
+    };
+    use futures::future;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_scroll_until_loaded() {
+        // The feed grows from 2 to 4 elements after the first scroll, nothing is loaded after the second one
+        let mut counts = vec![2, 4, 4].into_iter();
+        let mut client = MockScrapeClient::new();
+        client.expect_find_all().times(3).returning(move |_| {
+            let count = counts.next().unwrap_or_default();
+            Box::pin(future::ok((0..count).map(|_| mock_element("Card")).collect()))
+        });
+        client
+            .expect_execute()
+            .withf(|_, args| *args == vec![json!(null), json!(null)])
+            .times(2)
+            .returning(|_, _| Box::pin(future::ok(json!(null))));
+
+        let mut context = ScrapeContext::new(client, None);
+        let action = ScrollUntilLoaded::global(Selector::Css, Value::constant("article")).with_delay(0.0);
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_scroll_element_into_view() {
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_find_all()
+            .withf(|locator| *locator == Locator::Css("footer"))
+            .times(1)
+            .returning(|_| {
+                let mut element = MockScrapeElement::new();
+                element
+                    .expect_reference()
+                    .returning(|| Some(json!({"element": "footer"})));
+                let element: Box<dyn ScrapeElement> = Box::new(element);
+                Box::pin(future::ok(vec![element]))
+            });
+        client
+            .expect_execute()
+            .withf(|_, args| *args == vec![json!({"element": "footer"})])
+            .times(1)
+            .returning(|_, _| Box::pin(future::ok(json!(null))));
+
+        let mut context = ScrapeContext::new(client, None);
+        let action: Scroll =
+            serde_yaml::from_str("target: { IntoView: { selector: Css, query: { Constant: footer } } }").unwrap();
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+
+        // Current element is scrolled without the query
+        let action = Scroll::into_view();
+        assert_eq!(true, action.execute(&mut context).await.is_err());
+
+        let action = Scroll::element_into_view(Selector::Css, Value::constant("footer"), ElementScope::Current);
+        assert_eq!(true, action.execute(&mut context).await.is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    action::{ScrapeAction, ScrapeActionResult},
+    element::{Locator, ScrapeElement},
+    pipeline::{ScrapeContext, ScrapeError},
+    value::{to_string, Value, ValueType},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub enum OptionSelector {
+    /// Visible option text, compared with the whitespace collapsed
+    Text,
+    /// Option "value" attribute
+    Value,
+    /// Zero based option position
+    Index,
+}
+
+/// Selects the option of the current `<select>` element, the current element is left unchanged
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SelectOption {
+    pub by: OptionSelector,
+    pub option: Value,
+}
+
+impl SelectOption {
+    pub fn new(by: OptionSelector, option: Value) -> Self {
+        SelectOption { by, option }
+    }
+
+    pub fn by_text(option: Value) -> Self {
+        SelectOption::new(OptionSelector::Text, option)
+    }
+
+    pub fn by_value(option: Value) -> Self {
+        SelectOption::new(OptionSelector::Value, option)
+    }
+
+    pub fn by_index(option: Value) -> Self {
+        SelectOption::new(OptionSelector::Index, option)
+    }
+}
+
+impl Display for SelectOption {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(
+            fmt,
+            "select the option of the current element by {:?} from {}",
+            self.by, self.option
+        )
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for SelectOption {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        let option = self.option.resolve(context).await?;
+        let element = context.current_element.as_mut().ok_or(ScrapeError::MissingElement)?;
+        let mut options = element.find_all(Locator::Css("option")).await?;
+
+        let position = match self.by {
+            OptionSelector::Index => ValueType::Number
+                .convert(option)?
+                .as_u64()
+                .map(|index| index as usize)
+                .ok_or(ScrapeError::ValueConversionError(ValueType::Number))?,
+
+            OptionSelector::Text | OptionSelector::Value => {
+                let expected = to_string(option).ok_or(ScrapeError::ValueResolveError)?;
+                position(&mut options, self.by, &collapse(&expected))
+                    .await?
+                    .ok_or(ScrapeError::ElementQueryEmptyResult)?
+            }
+        };
+
+        match options.get_mut(position) {
+            Some(option) => option.click().await,
+            None => Err(ScrapeError::ElementQueryEmptyResult),
+        }
+    }
+}
+
+/// Position of the first option with the text or value matching the expected one
+async fn position(
+    options: &mut [Box<dyn ScrapeElement>],
+    by: OptionSelector,
+    expected: &str,
+) -> Result<Option<usize>, ScrapeError> {
+    for (idx, option) in options.iter_mut().enumerate() {
+        let actual = match by {
+            OptionSelector::Value => option.attr("value").await?,
+            _ => Some(option.text().await?),
+        };
+
+        if actual.map(|actual| collapse(&actual) == expected).unwrap_or(false) {
+            return Ok(Some(idx));
+        }
+    }
+
+    Ok(None)
+}
+
+fn collapse(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{ScrapeAction, SelectOption},
+        client::MockScrapeClient,
+        element::{Locator, MockScrapeElement, ScrapeElement},
+        pipeline::{ScrapeContext, ScrapeError},
+        value::Value,
+    };
+    use futures::future;
+    use serde_json::json;
+
+    fn select(selected: Option<usize>) -> Box<dyn ScrapeElement> {
+        let mut element = MockScrapeElement::new();
+        element
+            .expect_find_all()
+            .withf(|locator| *locator == Locator::Css("option"))
+            .returning(move |_| {
+                let options = vec![("1", " Москва "), ("2", "Санкт-Петербург")]
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, (value, text))| {
+                        let mut option = MockScrapeElement::new();
+                        option
+                            .expect_text()
+                            .returning(move || Box::pin(future::ok(text.to_owned())));
+                        option
+                            .expect_attr()
+                            .returning(move |_| Box::pin(future::ok(Some(value.to_owned()))));
+                        option
+                            .expect_click()
+                            .times(if selected == Some(idx) { 1 } else { 0 })
+                            .returning(|| Box::pin(future::ok(())));
+
+                        let option: Box<dyn ScrapeElement> = Box::new(option);
+                        option
+                    })
+                    .collect();
+
+                Box::pin(future::ok(options))
+            });
+
+        Box::new(element)
+    }
+
+    #[tokio::test]
+    async fn test_select_option() {
+        let actions = vec![
+            (0, SelectOption::by_text(Value::constant("Москва"))),
+            (1, SelectOption::by_value(Value::context("region"))),
+            (1, SelectOption::by_index(Value::constant("1"))),
+        ];
+
+        for (selected, action) in actions {
+            let mut context = ScrapeContext::new(MockScrapeClient::new(), json!({"region": 2}));
+            context.current_element = Some(select(Some(selected)));
+            assert_eq!(true, action.execute(&mut context).await.is_ok());
+        }
+
+        let mut context = ScrapeContext::new(MockScrapeClient::new(), None);
+        context.current_element = Some(select(None));
+        let action = SelectOption::by_text(Value::constant("Казань"));
+        assert_eq!(
+            true,
+            matches!(
+                action.execute(&mut context).await,
+                Err(ScrapeError::ElementQueryEmptyResult)
+            )
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    action::{ScrapeAction, ScrapeActionResult},
+    pipeline::{ScrapeContext, ScrapeError},
+    value::{JsonValue, Value, ValueType},
+};
+use async_trait::async_trait;
+use json_dotpath::DotPaths;
+use serde::{Deserialize, Serialize};
+use serde_json::Number;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+/// Stores the resolved value in the context values, the value is available to the following stages as
+/// `Value::Context`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetContextValue {
+    pub key: String,
+    pub value: Value,
+}
+
+impl SetContextValue {
+    pub fn new<T: Into<String>>(key: T, value: Value) -> Self {
+        SetContextValue { key: key.into(), value }
+    }
+}
+
+impl Display for SetContextValue {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(
+            fmt,
+            "set context value \"{}\" with the value from {}",
+            self.key, self.value
+        )
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for SetContextValue {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        let value = self.value.resolve(context).await?;
+        context
+            .values
+            .dot_set(&self.key, value)
+            .map_err(|_| ScrapeError::SetContextValueError)
+    }
+}
+
+/// Adds the step to the numeric context value, missing value is counted from zero
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IncrementContextValue {
+    pub key: String,
+    #[serde(default = "default_step")]
+    pub step: i64,
+}
+
+fn default_step() -> i64 {
+    1
+}
+
+impl IncrementContextValue {
+    pub fn new<T: Into<String>>(key: T) -> Self {
+        IncrementContextValue {
+            key: key.into(),
+            step: default_step(),
+        }
+    }
+
+    pub fn with_step(mut self, step: i64) -> Self {
+        self.step = step;
+        self
+    }
+}
+
+impl Display for IncrementContextValue {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "increment context value \"{}\" by {}", self.key, self.step)
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for IncrementContextValue {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        let current = context
+            .values
+            .dot_get::<JsonValue>(&self.key)
+            .map_err(|_| ScrapeError::ValueResolveError)?
+            .unwrap_or_default();
+
+        // Numeric strings are accepted, e.g. the page number captured from the element text
+        let value = match ValueType::Number.convert(current)? {
+            JsonValue::Null => JsonValue::from(self.step),
+            JsonValue::Number(number) => match number.as_i64() {
+                Some(number) => number
+                    .checked_add(self.step)
+                    .map(JsonValue::from)
+                    .ok_or(ScrapeError::ValueConversionError(ValueType::Number))?,
+                None => number
+                    .as_f64()
+                    .and_then(|number| Number::from_f64(number + self.step as f64))
+                    .map(JsonValue::Number)
+                    .ok_or(ScrapeError::ValueConversionError(ValueType::Number))?,
+            },
+            _ => return Err(ScrapeError::ValueConversionError(ValueType::Number)),
+        };
+
+        context
+            .values
+            .dot_set(&self.key, value)
+            .map_err(|_| ScrapeError::SetContextValueError)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{IncrementContextValue, ScrapeAction, SetContextValue},
+        client::MockScrapeClient,
+        pipeline::{ScrapeContext, ScrapeError},
+        transform::Transform,
+        value::{Value, ValueType},
+    };
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_context_values() {
+        let mut context = ScrapeContext::new(MockScrapeClient::new(), json!({"link": "/offer/1?csrf=abc"}));
+        let action = SetContextValue::new(
+            "session.token",
+            Value::context("link").transform(Transform::regex_capture("csrf=(\\w+)", 1).unwrap()),
+        );
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+
+        let action = IncrementContextValue::new("page");
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+
+        let action = IncrementContextValue::new("session.token");
+        assert_eq!(true, action.execute(&mut context).await.is_err());
+
+        let mut overflow = ScrapeContext::new(MockScrapeClient::new(), json!({ "page": i64::MAX }));
+        let action = IncrementContextValue::new("page");
+        assert_eq!(
+            true,
+            matches!(
+                action.execute(&mut overflow).await,
+                Err(ScrapeError::ValueConversionError(ValueType::Number))
+            )
+        );
+
+        assert_eq!(
+            json!({"link": "/offer/1?csrf=abc", "session": {"token": "abc"}, "page": 2}),
+            context.values
+        );
+    }
+}
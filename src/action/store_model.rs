@@ -22,6 +22,6 @@ impl ScrapeAction for StoreModel {
     async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
         let mut model = json!({});
         swap(&mut model, &mut context.model);
-        Ok(context.models.push(model))
+        context.store_model(model).await
     }
 }
@@ -0,0 +1,206 @@
+use crate::{
+    action::{ElementScope, ScrapeAction, ScrapeActionResult},
+    client::ScrapeClient,
+    pipeline::{ScrapeContext, ScrapeError},
+    value::Value,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter, Result as FormatResult},
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Sequence number distinguishing the generated file names with the same timestamp
+static FILE_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TakeScreenshot {
+    directory: PathBuf,
+    scope: ElementScope,
+    #[serde(default)]
+    file_name: Option<Value>,
+}
+
+impl TakeScreenshot {
+    pub fn new<P: Into<PathBuf>>(directory: P, scope: ElementScope) -> Self {
+        TakeScreenshot {
+            directory: directory.into(),
+            scope,
+            file_name: None,
+        }
+    }
+
+    pub fn page<P: Into<PathBuf>>(directory: P) -> Self {
+        TakeScreenshot::new(directory, ElementScope::Global)
+    }
+
+    pub fn current<P: Into<PathBuf>>(directory: P) -> Self {
+        TakeScreenshot::new(directory, ElementScope::Current)
+    }
+
+    /// File name is generated from the current timestamp by default. Characters not allowed in the file names,
+    /// including the path separators, are replaced, so the screenshot is always saved to the directory
+    pub fn with_file_name(mut self, file_name: Value) -> Self {
+        self.file_name = Some(file_name);
+        self
+    }
+}
+
+impl Display for TakeScreenshot {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(
+            fmt,
+            "take screenshot in {:?} scope to directory {}",
+            self.scope,
+            self.directory.display()
+        )
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for TakeScreenshot {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        let file_name = match self.file_name {
+            Some(ref file_name) => file_name
+                .resolve_string(context)
+                .await?
+                .map(|file_name| sanitize_file_name(&file_name))
+                .ok_or(ScrapeError::ValueResolveError)?,
+            None => format!("screenshot-{}.png", unique_suffix()),
+        };
+
+        let element = match self.scope {
+            ElementScope::Global => None,
+            ElementScope::Scoped => Some(context.scoped_element.as_mut().ok_or(ScrapeError::MissingElement)?),
+            ElementScope::Current => Some(context.current_element.as_mut().ok_or(ScrapeError::MissingElement)?),
+        };
+
+        let image = match element {
+            Some(element) => element.screenshot().await?,
+            None => context.client.screenshot().await?,
+        };
+
+        write_file(&self.directory, &file_name, &image)
+    }
+}
+
+/// Screenshot and page source of the failed stage, saved for the later investigation
+pub(crate) async fn capture_failure(
+    client: &mut Box<dyn ScrapeClient>,
+    directory: &Path,
+    stage: &str,
+) -> Result<(), ScrapeError> {
+    // Stage names are arbitrary, characters not allowed in the file names are replaced
+    let name = format!("error-{}-{}", unique_suffix(), sanitize_file_name(stage));
+
+    let image = client.screenshot().await?;
+    write_file(directory, &format!("{}.png", name), &image)?;
+
+    let source = client.source().await?;
+    write_file(directory, &format!("{}.html", name), source.as_bytes())
+}
+
+fn write_file(directory: &Path, file_name: &str, contents: &[u8]) -> Result<(), ScrapeError> {
+    fs::create_dir_all(directory)
+        .and_then(|_| fs::write(directory.join(file_name), contents))
+        .map_err(|error| ScrapeError::ScreenshotError(error.to_string()))
+}
+
+/// Only alphanumeric characters, `-`, `_` and `.` are kept, names starting with a dot are prefixed to avoid the
+/// relative directory names and hidden files
+fn sanitize_file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if name.is_empty() || name.starts_with('.') {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// Millisecond timestamp with the sequence number
+fn unique_suffix() -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+
+    format!("{}-{}", timestamp, FILE_SEQUENCE.fetch_add(1, Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{ScrapeAction, TakeScreenshot},
+        client::MockScrapeClient,
+        pipeline::{ScrapeContext, ScrapeError},
+        value::Value,
+    };
+    use futures::future;
+    use std::{env, fs};
+
+    #[tokio::test]
+    async fn test_page_screenshot() {
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_screenshot()
+            .times(1)
+            .returning(|| Box::pin(future::ok(vec![137, 80, 78, 71])));
+
+        let directory = env::temp_dir().join("scrapman-test-screenshot");
+        let action = TakeScreenshot::page(&directory).with_file_name(Value::constant("page.png"));
+
+        let mut context = ScrapeContext::new(client, None);
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+        assert_eq!(vec![137, 80, 78, 71], fs::read(directory.join("page.png")).unwrap());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_screenshot_file_name() {
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_screenshot()
+            .times(3)
+            .returning(|| Box::pin(future::ok(vec![137, 80, 78, 71])));
+
+        let directory = env::temp_dir().join("scrapman-test-screenshot-name");
+        let action = TakeScreenshot::page(&directory).with_file_name(Value::constant("../../offer 1.png"));
+
+        let mut context = ScrapeContext::new(client, None);
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+        assert_eq!(true, directory.join("_.._.._offer_1.png").exists());
+
+        // Generated names are unique within the same millisecond
+        let action = TakeScreenshot::page(&directory);
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+        assert_eq!(true, action.execute(&mut context).await.is_ok());
+        assert_eq!(3, fs::read_dir(&directory).unwrap().count());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_missing_element_screenshot() {
+        let action = TakeScreenshot::current(env::temp_dir());
+
+        let mut context = ScrapeContext::new(MockScrapeClient::new(), None);
+        let result = action.execute(&mut context).await;
+        assert_eq!(true, matches!(result, Err(ScrapeError::MissingElement)));
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{
+    action::{query_element::find_elements, ElementScope, ScrapeAction, ScrapeActionResult, Selector},
+    client::ScrapeClient,
+    element::ScrapeElement,
+    pipeline::{ScrapeContext, ScrapeError},
+    value::Value,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FormatResult};
+use tokio::time::{sleep, Duration, Instant};
+
+// Element is considered visible if it has a non-empty bounding box, and is not hidden with styles
+const IS_VISIBLE_SCRIPT: &str = r#"
+    const element = arguments[0];
+    const rect = element.getBoundingClientRect();
+    const style = window.getComputedStyle(element);
+    return rect.width > 0 && rect.height > 0 && style.visibility !== "hidden" && style.display !== "none";
+"#;
+
+// Element is considered clickable if it is visible, enabled, and is not covered by another element
+const IS_CLICKABLE_SCRIPT: &str = r#"
+    const element = arguments[0];
+    const rect = element.getBoundingClientRect();
+    const style = window.getComputedStyle(element);
+    if (rect.width === 0 || rect.height === 0 || style.visibility === "hidden" || style.display === "none") {
+        return false;
+    }
+
+    if (element.disabled || style.pointerEvents === "none") {
+        return false;
+    }
+
+    const x = rect.left + rect.width / 2;
+    const y = rect.top + rect.height / 2;
+    if (x < 0 || y < 0 || x > window.innerWidth || y > window.innerHeight) {
+        return true;
+    }
+
+    const target = document.elementFromPoint(x, y);
+    return target !== null && (target === element || element.contains(target));
+"#;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+pub enum WaitCondition {
+    Appear,
+    Disappear,
+    Visible,
+    Clickable,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WaitForElement {
+    selector: Selector,
+    query: Value,
+    scope: ElementScope,
+    condition: WaitCondition,
+    #[serde(default = "default_timeout")]
+    timeout: f64,
+    #[serde(default = "default_interval")]
+    interval: f64,
+}
+
+fn default_timeout() -> f64 {
+    10.0
+}
+
+fn default_interval() -> f64 {
+    0.5
+}
+
+impl WaitForElement {
+    pub fn new(selector: Selector, query: Value, scope: ElementScope, condition: WaitCondition) -> Self {
+        WaitForElement {
+            selector,
+            query,
+            scope,
+            condition,
+            timeout: default_timeout(),
+            interval: default_interval(),
+        }
+    }
+
+    pub fn global(selector: Selector, query: Value, condition: WaitCondition) -> Self {
+        WaitForElement::new(selector, query, ElementScope::Global, condition)
+    }
+
+    pub fn with_timeout(mut self, timeout: f64) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: f64) -> Self {
+        self.interval = interval;
+        self
+    }
+}
+
+impl Display for WaitForElement {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(
+            fmt,
+            "wait up to {} seconds for element with {:?} selector in {:?} scope with the query value from {} to {:?}",
+            self.timeout, self.selector, self.scope, self.query, self.condition
+        )
+    }
+}
+
+#[async_trait]
+#[typetag::serde]
+impl ScrapeAction for WaitForElement {
+    async fn execute(&self, context: &mut ScrapeContext) -> ScrapeActionResult {
+        let query = self
+            .query
+            .resolve_string(context)
+            .await?
+            .ok_or(ScrapeError::MissingQuery)?;
+
+        let started = Instant::now();
+        loop {
+            let elements = find_elements(context, self.selector, &query, self.scope).await?;
+            match self.condition {
+                WaitCondition::Disappear if elements.is_empty() => return Ok(()),
+                WaitCondition::Disappear => (),
+
+                // The first element matching the condition becomes the current one
+                condition => {
+                    for element in elements {
+                        if matches_condition(&mut context.client, element.as_ref(), condition).await? {
+                            context.current_element = Some(element);
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+
+            if started.elapsed().as_secs_f64() >= self.timeout {
+                return Err(ScrapeError::WaitTimeout);
+            }
+
+            sleep(Duration::from_secs_f64(self.interval)).await;
+        }
+    }
+}
+
+async fn matches_condition(
+    client: &mut Box<dyn ScrapeClient>,
+    element: &dyn ScrapeElement,
+    condition: WaitCondition,
+) -> Result<bool, ScrapeError> {
+    let script = match condition {
+        WaitCondition::Appear | WaitCondition::Disappear => return Ok(true),
+        WaitCondition::Visible => IS_VISIBLE_SCRIPT,
+        WaitCondition::Clickable => IS_CLICKABLE_SCRIPT,
+    };
+
+    let argument = element.reference().ok_or(ScrapeError::MissingElement)?;
+    client
+        .execute(script, vec![argument])
+        .await
+        .map(|result| result.as_bool().unwrap_or(false))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{ScrapeAction, Selector, WaitCondition, WaitForElement},
+        client::MockScrapeClient,
+        pipeline::{ScrapeContext, ScrapeError},
+        value::Value,
+    };
+    use futures::future;
+
+    fn context() -> ScrapeContext {
+        let mut client = MockScrapeClient::new();
+        client.expect_find_all().returning(|_| Box::pin(future::ok(Vec::new())));
+        ScrapeContext::new(client, None)
+    }
+
+    #[tokio::test]
+    async fn test_wait_disappear() {
+        let action = WaitForElement::global(Selector::Css, Value::constant("div.preload"), WaitCondition::Disappear);
+        assert_eq!(true, action.execute(&mut context()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_wait_timeout() {
+        let action = WaitForElement::global(Selector::Css, Value::constant("div.card"), WaitCondition::Appear)
+            .with_timeout(0.05)
+            .with_interval(0.01);
+
+        let result = action.execute(&mut context()).await;
+        assert_eq!(true, matches!(result, Err(ScrapeError::WaitTimeout)));
+    }
+}
@@ -0,0 +1,125 @@
+use clap::{App, Arg};
+use scrapman::{CsvSink, JsonLinesSink, JsonValue, ScrapePipeline, Scrapman};
+use std::{
+    error::Error,
+    ffi::OsStr,
+    fs::{read_to_string, File},
+    io::{stdout, Write},
+    path::Path,
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    pretty_env_logger::init_timed();
+
+    let args = App::new("scrapman")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Runs a scrape pipeline loaded from the definition file")
+        .arg(
+            Arg::with_name("pipeline")
+                .help("Pipeline definition file (YAML or JSON)")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("values")
+                .short("v")
+                .long("values")
+                .takes_value(true)
+                .help("Initial context values file (YAML or JSON)"),
+        )
+        .arg(
+            Arg::with_name("config")
+                .short("c")
+                .long("config")
+                .takes_value(true)
+                .help("Run configuration file (YAML or JSON): WebDriver URL, error capture and browser capabilities"),
+        )
+        .arg(
+            Arg::with_name("webdriver")
+                .short("w")
+                .long("webdriver")
+                .takes_value(true)
+                .default_value("http://localhost:4444")
+                .help("WebDriver server URL"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .help("Scraped models output file: JSON Lines (.jsonl), CSV (.csv) or JSON, stdout if omitted"),
+        )
+        .arg(
+            Arg::with_name("columns")
+                .long("columns")
+                .takes_value(true)
+                .use_delimiter(true)
+                .help("CSV output columns, comma separated. Inferred from the first model if omitted, the run fails on models with other attributes"),
+        )
+        .arg(
+            Arg::with_name("error-capture")
+                .short("e")
+                .long("error-capture")
+                .takes_value(true)
+                .help("Directory for the screenshots and page sources captured on stage failures"),
+        )
+        .get_matches();
+
+    let pipeline = ScrapePipeline::from_path(args.value_of("pipeline").unwrap_or_default())?;
+
+    // YAML parser is used for values files in both formats, since YAML is a superset of JSON
+    let values = match args.value_of("values") {
+        Some(path) => Some(serde_yaml::from_str::<JsonValue>(&read_to_string(path)?)?),
+        None => None,
+    };
+
+    // Command-line options take precedence over the run configuration
+    let mut scrapman = match args.value_of("config") {
+        Some(path) => serde_yaml::from_str::<Scrapman>(&read_to_string(path)?)?,
+        None => Scrapman::default(),
+    };
+    if args.occurrences_of("webdriver") > 0 || args.value_of("config").is_none() {
+        scrapman = scrapman.with_webdriver_url(args.value_of("webdriver").unwrap_or_default());
+    }
+
+    if let Some(directory) = args.value_of("error-capture") {
+        scrapman = scrapman.with_error_capture(directory);
+    }
+
+    // Line based formats are written as soon as the models are stored, JSON array - once the pipeline is completed
+    let output = args.value_of("output");
+    match output
+        .and_then(|path| Path::new(path).extension())
+        .and_then(OsStr::to_str)
+    {
+        Some("jsonl") => {
+            let sink = JsonLinesSink::create(output.unwrap_or_default())?;
+            scrapman.launch_with_sink(pipeline, values, sink).await?;
+        }
+
+        Some("csv") => match args.values_of("columns") {
+            Some(columns) => {
+                let sink = CsvSink::create_with_columns(output.unwrap_or_default(), columns.collect())?;
+                scrapman.launch_with_sink(pipeline, values, sink).await?;
+            }
+
+            None => {
+                let sink = CsvSink::create(output.unwrap_or_default())?.strict();
+                scrapman.launch_with_sink(pipeline, values, sink).await?;
+            }
+        },
+
+        _ => {
+            let context = scrapman.launch(pipeline, values).await?;
+            let mut output: Box<dyn Write> = match output {
+                Some(path) => Box::new(File::create(path)?),
+                None => Box::new(stdout()),
+            };
+
+            serde_json::to_writer_pretty(&mut output, &context.models)?;
+            writeln!(output)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,333 @@
+use crate::value::JsonValue;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map};
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum Browser {
+    Chrome,
+    Firefox,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct WindowSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Browser capabilities requested when the WebDriver session is created. Browser specific options are generated
+/// for the preset browser, or for both Chrome and Firefox if the browser is not set, so the options apply to whichever
+/// browser the WebDriver server launches. Raw capabilities are merged over the generated ones, raw arguments are
+/// appended to the generated ones
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BrowserCapabilities {
+    #[serde(default)]
+    pub browser: Option<Browser>,
+    #[serde(default)]
+    pub headless: bool,
+    #[serde(default)]
+    pub window_size: Option<WindowSize>,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub proxy: Option<String>,
+    #[serde(default)]
+    pub download_dir: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub raw: Map<String, JsonValue>,
+}
+
+impl BrowserCapabilities {
+    pub fn chrome() -> Self {
+        BrowserCapabilities {
+            browser: Some(Browser::Chrome),
+            ..BrowserCapabilities::default()
+        }
+    }
+
+    pub fn firefox() -> Self {
+        BrowserCapabilities {
+            browser: Some(Browser::Firefox),
+            ..BrowserCapabilities::default()
+        }
+    }
+
+    /// Capabilities are passed to the WebDriver server as is, no browser specific options are generated
+    pub fn raw(capabilities: Map<String, JsonValue>) -> Self {
+        BrowserCapabilities {
+            raw: capabilities,
+            ..BrowserCapabilities::default()
+        }
+    }
+
+    pub fn headless(mut self) -> Self {
+        self.headless = true;
+        self
+    }
+
+    pub fn with_window_size(mut self, width: u32, height: u32) -> Self {
+        self.window_size = Some(WindowSize { width, height });
+        self
+    }
+
+    pub fn with_user_agent<T: Into<String>>(mut self, user_agent: T) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn with_language<T: Into<String>>(mut self, language: T) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Proxy server address used for both HTTP and HTTPS connections, e.g. "localhost:8080"
+    pub fn with_proxy<T: Into<String>>(mut self, proxy: T) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn with_download_dir<P: Into<PathBuf>>(mut self, directory: P) -> Self {
+        self.download_dir = Some(directory.into());
+        self
+    }
+
+    pub fn with_capability<T: Into<String>>(mut self, name: T, value: JsonValue) -> Self {
+        self.raw.insert(name.into(), value);
+        self
+    }
+
+    /// WebDriver new session capabilities
+    pub fn to_capabilities(&self) -> Map<String, JsonValue> {
+        let mut capabilities = Map::new();
+        if let Some(ref proxy) = self.proxy {
+            capabilities.insert(
+                "proxy".into(),
+                json!({"proxyType": "manual", "httpProxy": proxy, "sslProxy": proxy}),
+            );
+        }
+
+        match self.browser {
+            Some(Browser::Chrome) => {
+                capabilities.insert("browserName".into(), json!("chrome"));
+                capabilities.insert("goog:chromeOptions".into(), self.chrome_options());
+            }
+
+            Some(Browser::Firefox) => {
+                capabilities.insert("browserName".into(), json!("firefox"));
+                capabilities.insert("moz:firefoxOptions".into(), self.firefox_options());
+            }
+
+            // Every server ignores the options of the other browser vendors
+            None if self.has_browser_options() => {
+                capabilities.insert("goog:chromeOptions".into(), self.chrome_options());
+                capabilities.insert("moz:firefoxOptions".into(), self.firefox_options());
+            }
+
+            None => (),
+        }
+
+        for (name, value) in &self.raw {
+            match capabilities.get_mut(name) {
+                Some(current) => merge(current, value.clone()),
+                None => {
+                    capabilities.insert(name.clone(), value.clone());
+                }
+            }
+        }
+
+        capabilities
+    }
+
+    fn has_browser_options(&self) -> bool {
+        self.headless
+            || self.window_size.is_some()
+            || self.user_agent.is_some()
+            || self.language.is_some()
+            || self.download_dir.is_some()
+    }
+
+    fn chrome_options(&self) -> JsonValue {
+        let mut args = Vec::new();
+        let mut prefs = Map::new();
+        if self.headless {
+            args.push("--headless".to_owned());
+        }
+
+        if let Some(WindowSize { width, height }) = self.window_size {
+            args.push(format!("--window-size={},{}", width, height));
+        }
+
+        if let Some(ref user_agent) = self.user_agent {
+            args.push(format!("--user-agent={}", user_agent));
+        }
+
+        if let Some(ref language) = self.language {
+            args.push(format!("--lang={}", language));
+            prefs.insert("intl.accept_languages".into(), json!(language));
+        }
+
+        if let Some(ref directory) = self.download_dir {
+            prefs.insert("download.default_directory".into(), json!(directory));
+            prefs.insert("download.prompt_for_download".into(), json!(false));
+        }
+
+        json!({"args": args, "prefs": prefs})
+    }
+
+    fn firefox_options(&self) -> JsonValue {
+        let mut args = Vec::new();
+        let mut prefs = Map::new();
+        if self.headless {
+            args.push("-headless".to_owned());
+        }
+
+        if let Some(WindowSize { width, height }) = self.window_size {
+            args.push(format!("--width={}", width));
+            args.push(format!("--height={}", height));
+        }
+
+        if let Some(ref user_agent) = self.user_agent {
+            prefs.insert("general.useragent.override".into(), json!(user_agent));
+        }
+
+        if let Some(ref language) = self.language {
+            prefs.insert("intl.accept_languages".into(), json!(language));
+        }
+
+        if let Some(ref directory) = self.download_dir {
+            prefs.insert("browser.download.dir".into(), json!(directory));
+            prefs.insert("browser.download.folderList".into(), json!(2));
+        }
+
+        json!({"args": args, "prefs": prefs})
+    }
+}
+
+/// Objects are merged recursively, arrays such as the browser arguments are concatenated skipping the duplicates, any
+/// other values are replaced
+fn merge(target: &mut JsonValue, value: JsonValue) {
+    match (target, value) {
+        (JsonValue::Array(target), JsonValue::Array(value)) => {
+            for value in value {
+                if !target.contains(&value) {
+                    target.push(value);
+                }
+            }
+        }
+
+        (JsonValue::Object(target), JsonValue::Object(value)) => {
+            for (name, value) in value {
+                match target.get_mut(&name) {
+                    Some(current) => merge(current, value),
+                    None => {
+                        target.insert(name, value);
+                    }
+                }
+            }
+        }
+
+        (target, value) => *target = value,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BrowserCapabilities;
+    use serde_json::json;
+
+    #[test]
+    fn test_chrome_capabilities() {
+        let capabilities = BrowserCapabilities::chrome()
+            .headless()
+            .with_window_size(1280, 800)
+            .with_language("ru-RU")
+            .with_proxy("localhost:8080")
+            .with_capability(
+                "goog:chromeOptions",
+                json!({"prefs": {"profile.managed_default_content_settings.images": 2}}),
+            );
+
+        assert_eq!(
+            json!({
+                "browserName": "chrome",
+                "proxy": {"proxyType": "manual", "httpProxy": "localhost:8080", "sslProxy": "localhost:8080"},
+                "goog:chromeOptions": {
+                    "args": ["--headless", "--window-size=1280,800", "--lang=ru-RU"],
+                    "prefs": {
+                        "intl.accept_languages": "ru-RU",
+                        "profile.managed_default_content_settings.images": 2
+                    }
+                }
+            }),
+            json!(capabilities.to_capabilities())
+        );
+    }
+
+    #[test]
+    fn test_browser_agnostic_capabilities() {
+        let capabilities = BrowserCapabilities::default().headless();
+        assert_eq!(
+            json!({
+                "goog:chromeOptions": {"args": ["--headless"], "prefs": {}},
+                "moz:firefoxOptions": {"args": ["-headless"], "prefs": {}}
+            }),
+            json!(capabilities.to_capabilities())
+        );
+
+        let capabilities = BrowserCapabilities::default().with_proxy("localhost:8080");
+        assert_eq!(false, capabilities.to_capabilities().contains_key("goog:chromeOptions"));
+    }
+
+    #[test]
+    fn test_deserialize_capabilities() {
+        let capabilities: BrowserCapabilities = serde_yaml::from_str(
+            r#"
+            browser: Firefox
+            headless: true
+            user_agent: scrapman
+            download_dir: /tmp/downloads
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            json!({
+                "browserName": "firefox",
+                "moz:firefoxOptions": {
+                    "args": ["-headless"],
+                    "prefs": {
+                        "general.useragent.override": "scrapman",
+                        "browser.download.dir": "/tmp/downloads",
+                        "browser.download.folderList": 2
+                    }
+                }
+            }),
+            json!(capabilities.to_capabilities())
+        );
+    }
+
+    #[test]
+    fn test_merge_raw_arguments() {
+        let capabilities = BrowserCapabilities::chrome()
+            .headless()
+            .with_language("ru-RU")
+            .with_capability(
+                "goog:chromeOptions",
+                json!({"args": ["--headless", "--disable-gpu"], "excludeSwitches": ["enable-automation"]}),
+            );
+
+        assert_eq!(
+            json!({
+                "browserName": "chrome",
+                "goog:chromeOptions": {
+                    "args": ["--headless", "--lang=ru-RU", "--disable-gpu"],
+                    "excludeSwitches": ["enable-automation"],
+                    "prefs": {"intl.accept_languages": "ru-RU"}
+                }
+            }),
+            json!(capabilities.to_capabilities())
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{
+    client::BLANK_URL,
+    pipeline::{ScrapeContext, ScrapeError},
+    value::JsonValue,
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{read_to_string, remove_file, rename, write},
+    io::ErrorKind,
+    path::Path,
+};
+
+/// Pipeline execution state saved before a checkpoint stage is executed, the execution is resumed from the stage.
+/// Scoped and current elements are not saved, so checkpoint stages must not depend on the elements found by the
+/// previous stages, e.g. a checkpoint stage opens a page or queries the elements in the global scope
+///
+/// Models emitted to a sink are delivered at least once: the ones emitted after the checkpoint was saved are emitted
+/// again once the execution is resumed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    /// Position of the top level pipeline stage
+    pub stage: usize,
+    /// Page opened in the session when the checkpoint was saved
+    #[serde(default)]
+    pub url: Option<String>,
+    pub values: JsonValue,
+    pub model: JsonValue,
+    /// Models accumulated in the context, models emitted to a sink are not included
+    #[serde(default)]
+    pub models: Vec<JsonValue>,
+}
+
+impl Checkpoint {
+    /// Captures the context state before the stage at the specified position is executed
+    pub async fn capture(context: &mut ScrapeContext, stage: usize) -> Self {
+        let url = match context.client.current_url().await {
+            Ok(url) if url != BLANK_URL => Some(url),
+            Ok(_) => None,
+            Err(error) => {
+                warn!("Failed to get the current URL: {}", error);
+                None
+            }
+        };
+
+        Checkpoint {
+            stage,
+            url,
+            values: context.values.clone(),
+            model: context.model.clone(),
+            models: context.models.clone(),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ScrapeError> {
+        let source = read_to_string(path).map_err(checkpoint_error)?;
+        serde_json::from_str(&source).map_err(checkpoint_error)
+    }
+
+    /// The file is replaced at once, so the previous checkpoint is kept if the process is terminated while saving
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ScrapeError> {
+        let path = path.as_ref();
+        let source = serde_json::to_string_pretty(self).map_err(checkpoint_error)?;
+        let temporary = path.with_extension("tmp");
+        write(&temporary, source).map_err(checkpoint_error)?;
+        rename(&temporary, path).map_err(checkpoint_error)
+    }
+
+    /// Removes the checkpoint file once the execution is completed, a missing file is not an error
+    pub fn clear<P: AsRef<Path>>(path: P) -> Result<(), ScrapeError> {
+        match remove_file(path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(checkpoint_error(error)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Restores the saved state in the context, the saved page is opened in the session
+    pub async fn restore(self, context: &mut ScrapeContext) -> Result<usize, ScrapeError> {
+        if let Some(ref url) = self.url {
+            context.client.goto(url).await?;
+        }
+
+        context.values = self.values;
+        context.model = self.model;
+        context.models = self.models;
+        Ok(self.stage)
+    }
+}
+
+fn checkpoint_error<E: ToString>(error: E) -> ScrapeError {
+    ScrapeError::CheckpointError(error.to_string())
+}
@@ -1,30 +1,28 @@
-use crate::ScrapeError;
+use crate::{
+    element::{Locator, ScrapeElement},
+    value::JsonValue,
+    ScrapeError,
+};
 use async_trait::async_trait;
-use fantoccini::{elements::Element, Client, Locator};
 
 #[cfg(test)]
 use mockall::automock;
 
+/// URL of the blank page a new session starts with
+pub const BLANK_URL: &str = "about:blank";
+
 #[async_trait]
 #[cfg_attr(test, automock)]
 pub trait ScrapeClient: Send + Sync {
     async fn goto(&mut self, url: &str) -> Result<(), ScrapeError>;
-    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Element>, ScrapeError>;
-    async fn disconnect(&mut self) -> Result<(), ScrapeError>;
-}
+    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError>;
+    async fn execute(&mut self, script: &str, args: Vec<JsonValue>) -> Result<JsonValue, ScrapeError>;
+    async fn execute_async(&mut self, script: &str, args: Vec<JsonValue>) -> Result<JsonValue, ScrapeError>;
+    async fn screenshot(&mut self) -> Result<Vec<u8>, ScrapeError>;
+    async fn source(&mut self) -> Result<String, ScrapeError>;
+    async fn current_url(&mut self) -> Result<String, ScrapeError>;
 
-#[async_trait]
-impl ScrapeClient for Client {
-    async fn goto(&mut self, url: &str) -> Result<(), ScrapeError> {
-        self.goto(url).await.map_err(ScrapeError::WebdriverCommandError)
-    }
-
-    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Element>, ScrapeError> {
-        self.find_all(search).await.map_err(ScrapeError::WebdriverCommandError)
-    }
-
-    async fn disconnect(&mut self) -> Result<(), ScrapeError> {
-        self.close_window().await.map_err(ScrapeError::WebdriverCommandError)?;
-        self.close().await.map_err(ScrapeError::WebdriverCommandError)
-    }
+    /// Performs the WebDriver input action sequences, the browser dispatches the events as trusted user input
+    async fn perform_actions(&mut self, actions: Vec<JsonValue>) -> Result<(), ScrapeError>;
+    async fn disconnect(&mut self) -> Result<(), ScrapeError>;
 }
@@ -0,0 +1,269 @@
+use crate::{
+    pipeline::{ScrapeContext, ScrapeError},
+    transform::Pattern,
+    value::{to_string, JsonValue, Value, ValueType},
+};
+use futures::future::{BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Condition {
+    /// Resolved values are equal as JSON values, use typed values to compare e.g. numbers with numeric strings
+    Equals {
+        value: Value,
+        expected: Value,
+    },
+    /// String value contains the expected value as a substring, array value contains the expected value as an item
+    Contains {
+        value: Value,
+        expected: Value,
+    },
+    /// String value matches the regex pattern
+    Matches {
+        value: Value,
+        pattern: Pattern,
+    },
+    /// Values are converted to numbers and compared
+    Compare {
+        value: Value,
+        comparison: Comparison,
+        expected: Value,
+    },
+    /// Value is resolved to a non-null value, missing current element is treated as a missing value
+    Exists(Value),
+    Not(Box<Condition>),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+}
+
+impl Display for Condition {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+impl Condition {
+    pub fn equals(value: Value, expected: Value) -> Self {
+        Condition::Equals { value, expected }
+    }
+
+    pub fn contains(value: Value, expected: Value) -> Self {
+        Condition::Contains { value, expected }
+    }
+
+    /// Fails with the `InvalidCondition` error kind if the pattern is not a valid regex
+    pub fn matches(value: Value, pattern: &str) -> Result<Self, ScrapeError> {
+        Ok(Condition::Matches {
+            value,
+            pattern: Pattern::new(pattern).map_err(|error| ScrapeError::InvalidCondition(error.to_string()))?,
+        })
+    }
+
+    pub fn compare(value: Value, comparison: Comparison, expected: Value) -> Self {
+        Condition::Compare {
+            value,
+            comparison,
+            expected,
+        }
+    }
+
+    pub fn exists(value: Value) -> Self {
+        Condition::Exists(value)
+    }
+
+    pub fn negate(self) -> Self {
+        Condition::Not(Box::new(self))
+    }
+
+    /// Evaluates the condition, missing values never satisfy the comparisons
+    pub fn evaluate<'a>(&'a self, context: &'a mut ScrapeContext) -> BoxFuture<'a, Result<bool, ScrapeError>> {
+        async move {
+            match self {
+                Condition::Equals { value, expected } => {
+                    Ok(value.resolve(context).await? == expected.resolve(context).await?)
+                }
+
+                Condition::Contains { value, expected } => {
+                    let value = value.resolve(context).await?;
+                    let expected = expected.resolve(context).await?;
+                    match (value, expected) {
+                        (JsonValue::Array(items), expected) => Ok(items.contains(&expected)),
+                        (_, JsonValue::Null) => Ok(false),
+                        (value, expected) => match (to_string(value), to_string(expected)) {
+                            (Some(value), Some(expected)) => Ok(value.contains(&expected)),
+                            _ => Ok(false),
+                        },
+                    }
+                }
+
+                Condition::Matches { value, pattern } => Ok(value
+                    .resolve_string(context)
+                    .await?
+                    .map(|value| pattern.is_match(&value))
+                    .unwrap_or(false)),
+
+                Condition::Compare {
+                    value,
+                    comparison,
+                    expected,
+                } => {
+                    let value = to_number(value.resolve(context).await?)?;
+                    let expected = to_number(expected.resolve(context).await?)?;
+                    match (value, expected) {
+                        (Some(value), Some(expected)) => Ok(match comparison {
+                            Comparison::Less => value < expected,
+                            Comparison::LessOrEqual => value <= expected,
+                            Comparison::Greater => value > expected,
+                            Comparison::GreaterOrEqual => value >= expected,
+                        }),
+                        _ => Ok(false),
+                    }
+                }
+
+                Condition::Exists(value) => match value.resolve(context).await {
+                    Ok(value) => Ok(!value.is_null()),
+                    Err(ScrapeError::MissingElement) => Ok(false),
+                    Err(error) => Err(error),
+                },
+
+                Condition::Not(condition) => condition.evaluate(context).await.map(|result| !result),
+
+                // Conditions are evaluated lazily, in the declaration order
+                Condition::All(conditions) => {
+                    for condition in conditions {
+                        if !condition.evaluate(context).await? {
+                            return Ok(false);
+                        }
+                    }
+
+                    Ok(true)
+                }
+
+                Condition::Any(conditions) => {
+                    for condition in conditions {
+                        if condition.evaluate(context).await? {
+                            return Ok(true);
+                        }
+                    }
+
+                    Ok(false)
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+fn to_number(value: JsonValue) -> Result<Option<f64>, ScrapeError> {
+    ValueType::Number.convert(value).map(|value| value.as_f64())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Comparison, Condition};
+    use crate::{
+        client::MockScrapeClient,
+        pipeline::{ScrapeContext, ScrapeError},
+        transform::Transform,
+        value::Value,
+    };
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_conditions() {
+        let mut context = ScrapeContext::new(
+            MockScrapeClient::new(),
+            json!({"price": "12 500", "tags": ["new"], "title": "Цена по запросу"}),
+        );
+
+        let conditions = vec![
+            (
+                true,
+                Condition::equals(Value::context("tags"), Value::constant(json!(["new"]))),
+            ),
+            (
+                true,
+                Condition::contains(Value::context("title"), Value::constant("по запросу")),
+            ),
+            (
+                true,
+                Condition::contains(Value::context("tags"), Value::constant("new")),
+            ),
+            (false, Condition::matches(Value::context("title"), r"^\d+").unwrap()),
+            (
+                true,
+                Condition::compare(
+                    Value::context("price").transform(Transform::StripNonDigits),
+                    Comparison::GreaterOrEqual,
+                    Value::constant(12500),
+                ),
+            ),
+            (false, Condition::exists(Value::context("missing"))),
+            (false, Condition::exists(Value::ElementText)),
+            (true, Condition::exists(Value::ElementText).negate()),
+            (
+                false,
+                Condition::All(vec![
+                    Condition::exists(Value::context("price")),
+                    Condition::exists(Value::context("missing")),
+                ]),
+            ),
+            (
+                true,
+                Condition::Any(vec![
+                    Condition::exists(Value::context("missing")),
+                    Condition::exists(Value::context("price")),
+                ]),
+            ),
+        ];
+
+        for (expected, condition) in conditions {
+            assert_eq!(
+                expected,
+                condition.evaluate(&mut context).await.unwrap(),
+                "{}",
+                condition
+            );
+        }
+
+        let condition = Condition::compare(Value::context("title"), Comparison::Less, Value::constant(1));
+        assert_eq!(true, condition.evaluate(&mut context).await.is_err());
+
+        assert_eq!(
+            true,
+            matches!(
+                Condition::matches(Value::context("title"), "("),
+                Err(ScrapeError::InvalidCondition(_))
+            )
+        );
+    }
+
+    #[test]
+    fn test_deserialize_condition() {
+        let condition: Condition = serde_yaml::from_str(
+            r#"
+            Any:
+              - Contains: { value: ElementText, expected: { Constant: "по запросу" } }
+              - Compare: { value: { Context: price }, comparison: Less, expected: { Constant: 1000 } }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            true,
+            matches!(condition, Condition::Any(ref conditions) if conditions.len() == 2)
+        );
+
+        let condition = serde_yaml::from_str::<Condition>("Matches: { value: ElementText, pattern: \"(\" }");
+        assert_eq!(true, condition.is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{pipeline::ScrapeError, value::JsonValue};
+use async_trait::async_trait;
+use std::fmt::Debug;
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Element search strategy supported by the scrape clients
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Locator<'a> {
+    Css(&'a str),
+    Id(&'a str),
+    LinkText(&'a str),
+    XPath(&'a str),
+}
+
+/// Page element found by the scrape client
+#[async_trait]
+#[cfg_attr(test, automock)]
+pub trait ScrapeElement: Send + Sync + Debug {
+    async fn text(&mut self) -> Result<String, ScrapeError>;
+    async fn attr(&mut self, name: &str) -> Result<Option<String>, ScrapeError>;
+    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError>;
+    async fn click(&mut self) -> Result<(), ScrapeError>;
+    async fn send_keys(&mut self, text: &str) -> Result<(), ScrapeError>;
+    async fn screenshot(&mut self) -> Result<Vec<u8>, ScrapeError>;
+
+    /// Element reference passed to the scripts executed by the client, if the client supports scripts
+    fn reference(&self) -> Option<JsonValue>;
+
+    fn clone_element(&self) -> Box<dyn ScrapeElement>;
+}
+
+impl Clone for Box<dyn ScrapeElement> {
+    fn clone(&self) -> Self {
+        self.clone_element()
+    }
+}
+
+pub(crate) fn boxed<E: ScrapeElement + 'static>(element: E) -> Box<dyn ScrapeElement> {
+    Box::new(element)
+}
+
+#[cfg(test)]
+impl Debug for MockScrapeElement {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("MockScrapeElement").finish()
+    }
+}
+
+/// Mock element with the specified text, clones of the element are mocked the same way
+#[cfg(test)]
+pub(crate) fn mock_element(text: &'static str) -> Box<dyn ScrapeElement> {
+    let mut element = MockScrapeElement::new();
+    element
+        .expect_text()
+        .returning(move || Box::pin(futures::future::ok(text.to_owned())));
+    element.expect_reference().returning(|| None);
+    element.expect_clone_element().returning(move || mock_element(text));
+    Box::new(element)
+}
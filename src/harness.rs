@@ -0,0 +1,207 @@
+use crate::{
+    pipeline::{ScrapeContext, ScrapeError, ScrapePipeline},
+    scrapman,
+    static_client::StaticClient,
+    validation::ValidationError,
+    value::JsonValue,
+};
+use std::{
+    env,
+    ffi::OsStr,
+    fs::{read_to_string, write},
+    path::{Path, PathBuf},
+};
+
+/// Snapshot files are overwritten with the actual models if the variable is set
+pub const UPDATE_SNAPSHOTS_VAR: &str = "SCRAPMAN_UPDATE_SNAPSHOTS";
+
+/// Pipeline execution against local HTML fixtures keyed by URL, used to test the models extracted by the pipeline.
+/// Opening a URL without the fixture fails the execution
+pub struct PipelineTest {
+    client: StaticClient,
+    values: Option<JsonValue>,
+}
+
+impl PipelineTest {
+    pub fn new() -> Self {
+        PipelineTest {
+            client: StaticClient::new().offline(),
+            values: None,
+        }
+    }
+
+    pub fn with_fixture<U: Into<String>, P: Into<PathBuf>>(mut self, url: U, path: P) -> Self {
+        self.client = self.client.with_fixture(url, path);
+        self
+    }
+
+    pub fn with_page<U: Into<String>, H: Into<String>>(mut self, url: U, html: H) -> Self {
+        self.client = self.client.with_page(url, html);
+        self
+    }
+
+    pub fn with_values(mut self, values: JsonValue) -> Self {
+        self.values = Some(values);
+        self
+    }
+
+    /// Validates and executes the pipeline, returns the stored models
+    pub async fn run(self, pipeline: &ScrapePipeline) -> Result<Vec<JsonValue>, ScrapeError> {
+        scrapman::validate(pipeline)?;
+        let mut context = ScrapeContext::new(self.client, self.values);
+        pipeline.execute(&mut context).await?;
+        Ok(context.models)
+    }
+
+    /// Compares the stored models with the snapshot file, files with ".json" extension are parsed as JSON, any other
+    /// as YAML. Panics if the pipeline execution fails, or the models do not match the snapshot
+    pub async fn assert_snapshot<P: AsRef<Path>>(self, pipeline: &ScrapePipeline, snapshot: P) {
+        let snapshot = snapshot.as_ref();
+        let models = match self.run(pipeline).await {
+            Ok(models) => models,
+            Err(ScrapeError::InvalidPipeline(errors)) => {
+                let errors: Vec<_> = errors.iter().map(ValidationError::to_string).collect();
+                panic!("invalid pipeline: {}", errors.join(", "))
+            }
+            Err(error) => panic!("pipeline execution failed: {}", error),
+        };
+
+        if env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+            if let Err(error) = save_snapshot(snapshot, &models) {
+                panic!("failed to update snapshot {}: {}", snapshot.display(), error);
+            }
+            return;
+        }
+
+        let expected = match load_snapshot(snapshot) {
+            Ok(expected) => expected,
+            Err(error) => panic!("failed to load snapshot {}: {}", snapshot.display(), error),
+        };
+
+        if let Some(mismatch) = compare(&expected, &models) {
+            panic!("models do not match snapshot {}: {}", snapshot.display(), mismatch);
+        }
+    }
+}
+
+impl Default for PipelineTest {
+    fn default() -> Self {
+        PipelineTest::new()
+    }
+}
+
+fn load_snapshot(path: &Path) -> Result<Vec<JsonValue>, String> {
+    let source = read_to_string(path).map_err(|error| error.to_string())?;
+    match path.extension().and_then(OsStr::to_str) {
+        Some("json") => serde_json::from_str(&source).map_err(|error| error.to_string()),
+        _ => serde_yaml::from_str(&source).map_err(|error| error.to_string()),
+    }
+}
+
+fn save_snapshot(path: &Path, models: &[JsonValue]) -> Result<(), String> {
+    let source = match path.extension().and_then(OsStr::to_str) {
+        Some("json") => serde_json::to_string_pretty(models).map_err(|error| error.to_string())?,
+        _ => serde_yaml::to_string(models).map_err(|error| error.to_string())?,
+    };
+
+    write(path, source).map_err(|error| error.to_string())
+}
+
+/// Description of the first difference between the expected and the actual models
+fn compare(expected: &[JsonValue], actual: &[JsonValue]) -> Option<String> {
+    let pretty = |model: &JsonValue| serde_json::to_string_pretty(model).unwrap_or_default();
+    for (idx, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+        if expected != actual {
+            return Some(format!(
+                "model {} differs\nexpected: {}\nactual: {}",
+                idx,
+                pretty(expected),
+                pretty(actual)
+            ));
+        }
+    }
+
+    if actual.len() > expected.len() {
+        Some(format!(
+            "{} unexpected models, the first one: {}",
+            actual.len() - expected.len(),
+            pretty(&actual[expected.len()])
+        ))
+    } else if actual.len() < expected.len() {
+        Some(format!("{} expected models are missing", expected.len() - actual.len()))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{OpenUrl, QueryElement, Selector, SetModelAttribute, StoreModel},
+        harness::PipelineTest,
+        pipeline::{ScrapeError, ScrapePipeline},
+        stage::{FlowControl, ScrapeStage},
+        transform::Transform,
+        value::{Value, ValueType},
+    };
+    use serde_json::json;
+
+    fn pipeline() -> ScrapePipeline {
+        ScrapePipeline::default()
+            .push(OpenUrl::new(Value::context("start_url")))
+            .push(
+                QueryElement::global(Selector::Css, Value::constant("article.card")).for_each(
+                    ScrapePipeline::default()
+                        .push(QueryElement::scoped(Selector::Css, Value::constant("a.title")))
+                        .push(SetModelAttribute::new("title", Value::ElementText))
+                        .push(SetModelAttribute::new("link", Value::element_attribute("href")))
+                        .push(
+                            ScrapeStage::from(QueryElement::scoped(Selector::Css, Value::constant("span.price")))
+                                .on_any_error(FlowControl::goto("Store")),
+                        )
+                        .push(SetModelAttribute::new(
+                            "price",
+                            Value::ElementText
+                                .transform(Transform::StripNonDigits)
+                                .typed(ValueType::Number),
+                        ))
+                        .push(ScrapeStage::from(StoreModel).with_name("Store")),
+                ),
+            )
+    }
+
+    #[tokio::test]
+    async fn test_fixture_snapshot() {
+        PipelineTest::new()
+            .with_fixture("https://example.com/search", "data/fixtures/search.html")
+            .with_values(json!({"start_url": "https://example.com/search"}))
+            .assert_snapshot(&pipeline(), "data/fixtures/search.models.yaml")
+            .await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "model 0 differs")]
+    async fn test_snapshot_mismatch() {
+        PipelineTest::new()
+            .with_page(
+                "https://example.com/search",
+                r#"<article class="card"><a class="title" href="/offer/9">Other</a></article>"#,
+            )
+            .with_values(json!({"start_url": "https://example.com/search"}))
+            .assert_snapshot(&pipeline(), "data/fixtures/search.models.yaml")
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_missing_fixture() {
+        let result = PipelineTest::new()
+            .with_values(json!({"start_url": "https://example.com/search"}))
+            .run(&pipeline())
+            .await;
+
+        match result {
+            Err(ScrapeError::FixtureError(error)) => assert_eq!("no fixture for https://example.com/search", error),
+            _ => panic!("unexpected result {:?}", result),
+        }
+    }
+}
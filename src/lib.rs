@@ -1,17 +1,43 @@
 pub mod action;
+pub mod capabilities;
+pub mod checkpoint;
 pub mod client;
+pub mod condition;
+pub mod element;
+pub mod harness;
+pub mod loader;
 pub mod pipeline;
+pub mod replay;
 pub mod scrapman;
+pub mod sink;
 pub mod stage;
+pub mod static_client;
+pub mod transform;
+pub mod validation;
 pub mod value;
+pub mod webdriver;
 
 pub use crate::{
     action::{
-        ClickElement, ElementScope, FillElement, OpenUrl, Pause, QueryElement, ScrapeAction, ScrapeActionResult,
-        Selector, SetModelAttribute, StoreModel,
+        Assert, ClickElement, DoubleClickElement, DragElement, DragTarget, ElementScope, ExecuteScript, FillElement,
+        HoverElement, If, IncrementContextValue, Key, MouseInput, OpenUrl, OptionSelector, Pause, PressKeys,
+        QueryElement, RightClickElement, ScrapeAction, ScrapeActionResult, ScriptArgument, ScriptResultTarget, Scroll,
+        ScrollTarget, ScrollUntilLoaded, SelectOption, Selector, SetContextValue, SetModelAttribute, StoreModel,
+        TakeScreenshot, WaitCondition, WaitForElement,
     },
-    pipeline::{ScrapeContext, ScrapeError, ScrapePipeline},
-    scrapman::Scrapman,
-    stage::{FlowControl, ScrapeStage},
-    value::{JsonValue, Value},
+    capabilities::{Browser, BrowserCapabilities, WindowSize},
+    checkpoint::Checkpoint,
+    condition::{Comparison, Condition},
+    harness::PipelineTest,
+    loader::PipelineLoadError,
+    pipeline::{ScrapeContext, ScrapeError, ScrapeErrorKind, ScrapePipeline},
+    replay::{Recording, RecordingClient, ReplayClient},
+    scrapman::{LaunchOutcome, Scrapman, SessionRecovery},
+    sink::{ChannelSink, CsvSink, JsonLinesSink, ModelSink},
+    stage::{Backoff, FlowControl, ScrapeStage},
+    static_client::StaticClient,
+    transform::{Pattern, Transform},
+    validation::ValidationError,
+    value::{JsonValue, Value, ValueType},
+    webdriver::WebDriverClient,
 };
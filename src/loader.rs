@@ -0,0 +1,210 @@
+use crate::{pipeline::ScrapePipeline, stage::ScrapeStage, validation::stage_path, value::JsonValue};
+use serde_json::json;
+use std::{
+    error::Error,
+    ffi::OsStr,
+    fmt::{Display, Formatter, Result as FormatResult},
+    fs::read_to_string,
+    io,
+    path::Path,
+};
+
+#[derive(Debug)]
+pub enum PipelineLoadError {
+    Io(io::Error),
+    Syntax(String),
+    Definition { path: String, message: String },
+}
+
+impl Display for PipelineLoadError {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            PipelineLoadError::Io(error) => {
+                write!(fmt, "failed to read pipeline definition: {}", error)
+            }
+
+            PipelineLoadError::Syntax(error) => {
+                write!(fmt, "malformed pipeline definition: {}", error)
+            }
+
+            PipelineLoadError::Definition { path, message } => {
+                write!(fmt, "invalid pipeline definition at {}: {}", path, message)
+            }
+        }
+    }
+}
+
+impl Error for PipelineLoadError {}
+
+impl From<io::Error> for PipelineLoadError {
+    fn from(error: io::Error) -> Self {
+        PipelineLoadError::Io(error)
+    }
+}
+
+impl ScrapePipeline {
+    pub fn from_yaml_str(source: &str) -> Result<Self, PipelineLoadError> {
+        serde_yaml::from_str::<JsonValue>(source)
+            .map_err(|error| PipelineLoadError::Syntax(error.to_string()))
+            .and_then(ScrapePipeline::from_value)
+    }
+
+    pub fn from_json_str(source: &str) -> Result<Self, PipelineLoadError> {
+        serde_json::from_str::<JsonValue>(source)
+            .map_err(|error| PipelineLoadError::Syntax(error.to_string()))
+            .and_then(ScrapePipeline::from_value)
+    }
+
+    /// Loads the pipeline definition file, files with ".json" extension are parsed as JSON, any other as YAML
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, PipelineLoadError> {
+        let path = path.as_ref();
+        let source = read_to_string(path)?;
+        match path.extension().and_then(OsStr::to_str) {
+            Some("json") => ScrapePipeline::from_json_str(&source),
+            _ => ScrapePipeline::from_yaml_str(&source),
+        }
+    }
+
+    /// Pipeline definition is either an object with "stages" list, or the stages list itself
+    pub fn from_value(value: JsonValue) -> Result<Self, PipelineLoadError> {
+        let value = match value {
+            JsonValue::Array(_) => json!({ "stages": value }),
+            value => value,
+        };
+
+        serde_json::from_value(value.clone()).map_err(|error| match find_invalid_stage(&value) {
+            Some((path, message)) => PipelineLoadError::Definition { path, message },
+            None => PipelineLoadError::Definition {
+                path: "pipeline".into(),
+                message: error.to_string(),
+            },
+        })
+    }
+}
+
+/// Stages are deserialized one by one to locate the first invalid one, nested pipelines in any action field are
+/// inspected recursively
+fn find_invalid_stage(pipeline: &JsonValue) -> Option<(String, String)> {
+    let stages = pipeline.get("stages")?.as_array()?;
+    for (idx, stage) in stages.iter().enumerate() {
+        if let Err(error) = serde_json::from_value::<ScrapeStage>(stage.clone()) {
+            let path = stage_path(idx, stage.get("name").and_then(JsonValue::as_str));
+
+            let nested = stage
+                .get("action")
+                .and_then(JsonValue::as_object)
+                .into_iter()
+                .flatten()
+                .filter(|(_, value)| value.get("stages").is_some())
+                .find_map(|(field, value)| {
+                    find_invalid_stage(value)
+                        .map(|(nested_path, message)| (format!("{} > {}.{}", path, field, nested_path), message))
+                });
+
+            return Some(nested.unwrap_or_else(|| (path, error.to_string())));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{loader::PipelineLoadError, pipeline::ScrapePipeline};
+
+    #[test]
+    fn test_load_yaml() {
+        let pipeline = ScrapePipeline::from_yaml_str(
+            r#"
+            stages:
+              - action:
+                  type: OpenUrl
+                  url:
+                    Context: start_url
+              - name: Cards
+                action:
+                  type: QueryElement
+                  selector: Css
+                  query:
+                    Constant: article
+                  scope: Global
+                  for_each:
+                    stages:
+                      - action:
+                          type: StoreModel
+                on_error: Quit
+            "#,
+        );
+
+        assert_eq!(true, pipeline.is_ok());
+    }
+
+    #[test]
+    fn test_load_json_stages_list() {
+        let pipeline = ScrapePipeline::from_json_str(r#"[{"action": {"type": "TestSuccess"}, "on_complete": "Quit"}]"#);
+        assert_eq!(true, pipeline.is_ok());
+    }
+
+    #[test]
+    fn test_invalid_stage() {
+        let pipeline = ScrapePipeline::from_yaml_str(
+            r#"
+            - action:
+                type: TestSuccess
+            - name: Cards
+              action:
+                type: QueryElement
+                selector: Css
+                query:
+                  Constant: article
+                scope: Global
+                for_each:
+                  stages:
+                    - action:
+                        type: TestSuccess
+                    - action:
+                        type: MissingAction
+            "#,
+        );
+
+        match pipeline {
+            Err(PipelineLoadError::Definition { path, .. }) => {
+                assert_eq!("stages[1] \"Cards\" > for_each.stages[1]", path)
+            }
+            result => panic!("unexpected load result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_invalid_nested_stage_in_any_field() {
+        let pipeline = ScrapePipeline::from_yaml_str(
+            r#"
+            - action:
+                type: UnknownLoop
+                body:
+                  stages:
+                    - action:
+                        type: MissingAction
+            "#,
+        );
+
+        match pipeline {
+            Err(PipelineLoadError::Definition { path, .. }) => assert_eq!("stages[0] > body.stages[0]", path),
+            result => panic!("unexpected load result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_load_example() {
+        let pipeline = ScrapePipeline::from_path("data/cian_pipeline.yaml");
+        assert_eq!(true, pipeline.is_ok());
+    }
+
+    #[test]
+    fn test_malformed_definition() {
+        match ScrapePipeline::from_json_str("{") {
+            Err(PipelineLoadError::Syntax(_)) => (),
+            result => panic!("unexpected load result: {:?}", result),
+        }
+    }
+}
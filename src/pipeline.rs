@@ -1,12 +1,14 @@
 use crate::{
+    action::capture_failure,
+    checkpoint::Checkpoint,
     client::ScrapeClient,
-    stage::{FlowControl, ScrapeStage},
-    value::JsonValue,
-};
-use fantoccini::{
-    elements::Element,
-    error::{CmdError, NewSessionError},
+    element::ScrapeElement,
+    sink::ModelSink,
+    stage::{clamp_delay, FlowControl, ScrapeStage},
+    validation::ValidationError,
+    value::{JsonValue, ValueType},
 };
+use fantoccini::error::{CmdError, NewSessionError};
 use futures::future::{BoxFuture, FutureExt};
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
@@ -14,6 +16,7 @@ use serde_json::json;
 use std::{
     error::Error,
     fmt::{Display, Formatter, Result as FormatResult},
+    path::PathBuf,
 };
 use tokio::time::{sleep, Duration};
 
@@ -30,71 +33,159 @@ impl ScrapePipeline {
         self
     }
 
+    pub fn stages(&self) -> &[ScrapeStage] {
+        &self.stages
+    }
+
+    /// Position of the first stage with the specified name
+    pub fn stage_position(&self, name: &str) -> Option<usize> {
+        self.stages.iter().position(|stage| match &stage.name {
+            Some(stage_name) => name.eq(stage_name),
+            _ => false,
+        })
+    }
+
+    /// Position the execution is resumed from after the failure of the specified stage: the closest checkpoint stage
+    /// preceding the failed one, or the failed stage itself
+    pub fn resume_position(&self, failed: usize) -> usize {
+        self.stages
+            .iter()
+            .take(failed + 1)
+            .rposition(|stage| stage.checkpoint)
+            .unwrap_or(failed)
+    }
+
     pub fn execute<'a>(&'a self, context: &'a mut ScrapeContext) -> BoxFuture<'a, ScrapePipelineResult> {
+        self.run(context, 0, false)
+    }
+
+    /// Top level pipeline execution starting with the stage at the specified position. The context state is saved to
+    /// the context checkpoint file before every checkpoint stage is executed
+    pub fn execute_from<'a>(
+        &'a self,
+        context: &'a mut ScrapeContext,
+        start: usize,
+    ) -> BoxFuture<'a, ScrapePipelineResult> {
+        self.run(context, start, true)
+    }
+
+    fn run<'a>(
+        &'a self,
+        context: &'a mut ScrapeContext,
+        start: usize,
+        top_level: bool,
+    ) -> BoxFuture<'a, ScrapePipelineResult> {
         async move {
-            let mut idx = 0;
-            loop {
-                match self.stages.get(idx) {
-                    Some(stage) => {
-                        match stage.name {
-                            Some(ref name) => info!("Executing {}: {}", name, stage.action),
-                            None => info!("Executing: {}", stage.action),
-                        }
+            let mut idx = start;
+
+            // Executions in a row of the current stage, reset once the stage is left
+            let mut attempts: u32 = 0;
+            while let Some(stage) = self.stages.get(idx) {
+                match stage.name {
+                    Some(ref name) => info!("Executing {}: {}", name, stage.action),
+                    None => info!("Executing: {}", stage.action),
+                }
 
-                        // Stage action execution, flow control evaluation based on the result
-                        let flow: &FlowControl;
-                        match stage.action.execute(context).await {
-                            // "On complete" branch is executed
-                            Ok(_) => flow = &stage.on_complete,
+                // Checkpoint is saved once the stage is entered, not on the repeated attempts. Checkpoint failure is
+                // propagated, since the execution could not be resumed otherwise
+                let entered = attempts == 0;
+                if let (true, true, true, Some(path)) =
+                    (top_level, entered, stage.checkpoint, context.checkpoint.clone())
+                {
+                    info!("Saving checkpoint to {}", path.display());
+                    Checkpoint::capture(context, idx).await.save(&path)?;
+                }
 
-                            // Internal client error - pipeline execution is stopped, the error is propagated
-                            Err(e @ ScrapeError::WebdriverCommandError(_)) => return Err(e),
+                // Stage action execution, flow control evaluation based on the result
+                let flow: &FlowControl;
+                match stage.action.execute_with_flow(context).await {
+                    // Flow chosen by the action or "on complete" branch is executed
+                    Ok(action_flow) => flow = action_flow.unwrap_or(&stage.on_complete),
+
+                    // Internal client error - pipeline execution is stopped, the error is propagated. Nested
+                    // pipelines propagate it to the enclosing action, so the failed stage ends up pointing to
+                    // the top level stage
+                    Err(e @ ScrapeError::WebdriverCommandError(_)) => {
+                        context.failed_stage = Some(idx);
+                        return Err(e);
+                    }
 
-                            // Stage action execution failure - "on error" branch is executed
-                            Err(error) => {
-                                warn!("Action execution failure: {}", error);
-                                flow = &stage.on_error;
+                    // Model output or replay fixture failure - pipeline execution is stopped regardless of
+                    // the flow control, to prevent the data loss or the replay silently diverging
+                    Err(e) if e.is_fatal() => return Err(e),
+
+                    // Stage action execution failure - error kind specific or "on error" branch is executed
+                    Err(error) => {
+                        warn!("Action execution failure: {}", error);
+                        flow = stage.error_flow(error.kind());
+
+                        // Only unexpected failures are captured, errors with kind specific flow control are
+                        // handled by the pipeline. Failure capture errors are not propagated, the pipeline
+                        // execution goes on
+                        let handled = stage.on_error_kind.contains_key(&error.kind());
+                        if let (false, Some(directory)) = (handled, context.error_capture.as_ref()) {
+                            let stage_name = stage.name.clone().unwrap_or_else(|| idx.to_string());
+                            if let Err(error) = capture_failure(&mut context.client, directory, &stage_name).await {
+                                warn!("Failure capture error: {}", error);
                             }
                         }
+                    }
+                }
 
-                        match flow {
-                            // Following pipeline stage is executed
-                            FlowControl::Continue => idx += 1,
+                attempts += 1;
+                let flow = match flow {
+                    // Repeat attempts are exhausted - fallback flow is executed
+                    FlowControl::Repeat {
+                        max_attempts: Some(max_attempts),
+                        fallback,
+                        ..
+                    } if attempts >= *max_attempts => {
+                        info!("Stage repeated {} times, applying fallback flow control", attempts);
+                        fallback.as_deref().unwrap_or(&FlowControl::Continue)
+                    }
+                    flow => flow,
+                };
 
-                            // Pipeline execution is stopped
-                            FlowControl::Quit => {
-                                info!("Flow control quit, stopping the pipeline execution");
-                                break;
-                            }
+                if !matches!(flow, FlowControl::Repeat { .. }) {
+                    attempts = 0;
+                }
 
-                            // Pipeline execution is redirected to a named stage
-                            FlowControl::Goto(next_stage) => {
-                                info!("Flow control redirection to stage \"{}\"", next_stage);
-                                match self.stages.iter().position(|stage| match &stage.name {
-                                    Some(name) => next_stage.eq(name),
-                                    _ => false,
-                                }) {
-                                    Some(pos) => idx = pos,
-                                    None => return Err(ScrapeError::MissingPipelineStage),
-                                }
-                            }
+                match flow {
+                    // Following pipeline stage is executed
+                    FlowControl::Continue => idx += 1,
 
-                            // Current pipeline stage execution is repeated after an optional delay
-                            FlowControl::Repeat { delay } => {
-                                match delay {
-                                    Some(x) => info!("Repeating stage after {} seconds", x),
-                                    None => info!("Repeating stage immediately"),
-                                };
+                    // Pipeline execution is stopped
+                    FlowControl::Quit => {
+                        info!("Flow control quit, stopping the pipeline execution");
+                        break;
+                    }
 
-                                if let Some(delay) = *delay {
-                                    sleep(Duration::from_secs_f64(delay)).await;
-                                }
-                            }
-                        };
+                    // Pipeline execution is redirected to a named stage
+                    FlowControl::Goto(next_stage) => {
+                        info!("Flow control redirection to stage \"{}\"", next_stage);
+                        match self.stage_position(next_stage) {
+                            Some(pos) => idx = pos,
+                            None => return Err(ScrapeError::MissingPipelineStage),
+                        }
                     }
 
-                    None => break,
-                }
+                    // Current pipeline stage execution is repeated after an optional delay
+                    FlowControl::Repeat { delay, backoff, .. } => {
+                        let delay = match (delay, backoff) {
+                            (Some(delay), Some(backoff)) => Some(backoff.delay(*delay, attempts)),
+                            (delay, _) => delay.map(clamp_delay),
+                        };
+
+                        match delay {
+                            Some(x) => info!("Repeating stage after {} seconds", x),
+                            None => info!("Repeating stage immediately"),
+                        };
+
+                        if let Some(delay) = delay {
+                            sleep(Duration::from_secs_f64(delay)).await;
+                        }
+                    }
+                };
             }
 
             Ok(())
@@ -108,37 +199,163 @@ pub struct ScrapeContext {
     pub model: JsonValue,
     pub values: JsonValue,
     pub models: Vec<JsonValue>,
-    pub scoped_element: Option<Element>,
-    pub current_element: Option<Element>,
+    pub sink: Option<Box<dyn ModelSink>>,
+    pub scoped_element: Option<Box<dyn ScrapeElement>>,
+    pub current_element: Option<Box<dyn ScrapeElement>>,
+    pub error_capture: Option<PathBuf>,
+    pub checkpoint: Option<PathBuf>,
+    /// Position of the stage which failed with the WebDriver command error
+    pub(crate) failed_stage: Option<usize>,
 }
 
 impl ScrapeContext {
     pub fn new<C: ScrapeClient + 'static, V: Into<Option<JsonValue>>>(client: C, values: V) -> Self {
+        ScrapeContext::with_client(Box::new(client), values)
+    }
+
+    pub fn with_client<V: Into<Option<JsonValue>>>(client: Box<dyn ScrapeClient>, values: V) -> Self {
         ScrapeContext {
-            client: Box::new(client),
+            client,
             model: json!({}),
             values: values.into().unwrap_or(json!({})),
             models: Vec::new(),
+            sink: None,
             current_element: None,
             scoped_element: None,
+            error_capture: None,
+            checkpoint: None,
+            failed_stage: None,
         }
     }
+
+    /// Stored models are emitted to the sink instead of being accumulated in the context
+    pub fn with_sink<S: ModelSink + 'static>(mut self, sink: S) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Screenshot and page source are saved to the directory whenever a stage execution fails
+    pub fn with_error_capture<P: Into<PathBuf>>(mut self, directory: P) -> Self {
+        self.error_capture = Some(directory.into());
+        self
+    }
+
+    /// Context state is saved to the file before every top level checkpoint stage is executed
+    pub fn with_checkpoint<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.checkpoint = Some(path.into());
+        self
+    }
+
+    pub async fn store_model(&mut self, model: JsonValue) -> Result<(), ScrapeError> {
+        match self.sink {
+            Some(ref mut sink) => sink.store(model).await,
+            None => {
+                self.models.push(model);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ScrapeErrorKind {
+    ValueResolveError,
+    ValueTransformError,
+    ValueConversionError,
+    ElementQueryEmptyResult,
+    WaitTimeout,
+    MissingElement,
+    MissingUrl,
+    MissingQuery,
+    MissingPipelineStage,
+    InvalidPipeline,
+    SetModelAttributeError,
+    SetContextValueError,
+    AssertionFailed,
+    InvalidCondition,
+    ScriptError,
+    ScreenshotError,
+    PageLoadError,
+    InvalidQuery,
+    UnsupportedOperation,
+    FixtureError,
+    ModelSinkError,
+    CheckpointError,
+    TestError,
+    WebdriverConnectionError,
+    WebdriverCommandError,
 }
 
 #[derive(Debug)]
 pub enum ScrapeError {
     ValueResolveError,
+    ValueTransformError,
+    ValueConversionError(ValueType),
     ElementQueryEmptyResult,
+    WaitTimeout,
     MissingElement,
     MissingUrl,
     MissingQuery,
     MissingPipelineStage,
+    InvalidPipeline(Vec<ValidationError>),
     SetModelAttributeError,
+    SetContextValueError,
+    AssertionFailed(String),
+    InvalidCondition(String),
+    ScriptError(String),
+    ScreenshotError(String),
+    PageLoadError(String),
+    InvalidQuery(String),
+    UnsupportedOperation(String),
+    FixtureError(String),
+    ModelSinkError(String),
+    CheckpointError(String),
     TestError,
     WebdriverConnectionError(NewSessionError),
     WebdriverCommandError(CmdError),
 }
 
+impl ScrapeError {
+    pub fn kind(&self) -> ScrapeErrorKind {
+        match self {
+            ScrapeError::ValueResolveError => ScrapeErrorKind::ValueResolveError,
+            ScrapeError::ValueTransformError => ScrapeErrorKind::ValueTransformError,
+            ScrapeError::ValueConversionError(_) => ScrapeErrorKind::ValueConversionError,
+            ScrapeError::ElementQueryEmptyResult => ScrapeErrorKind::ElementQueryEmptyResult,
+            ScrapeError::WaitTimeout => ScrapeErrorKind::WaitTimeout,
+            ScrapeError::MissingElement => ScrapeErrorKind::MissingElement,
+            ScrapeError::MissingUrl => ScrapeErrorKind::MissingUrl,
+            ScrapeError::MissingQuery => ScrapeErrorKind::MissingQuery,
+            ScrapeError::MissingPipelineStage => ScrapeErrorKind::MissingPipelineStage,
+            ScrapeError::InvalidPipeline(_) => ScrapeErrorKind::InvalidPipeline,
+            ScrapeError::SetModelAttributeError => ScrapeErrorKind::SetModelAttributeError,
+            ScrapeError::SetContextValueError => ScrapeErrorKind::SetContextValueError,
+            ScrapeError::AssertionFailed(_) => ScrapeErrorKind::AssertionFailed,
+            ScrapeError::InvalidCondition(_) => ScrapeErrorKind::InvalidCondition,
+            ScrapeError::ScriptError(_) => ScrapeErrorKind::ScriptError,
+            ScrapeError::ScreenshotError(_) => ScrapeErrorKind::ScreenshotError,
+            ScrapeError::PageLoadError(_) => ScrapeErrorKind::PageLoadError,
+            ScrapeError::InvalidQuery(_) => ScrapeErrorKind::InvalidQuery,
+            ScrapeError::UnsupportedOperation(_) => ScrapeErrorKind::UnsupportedOperation,
+            ScrapeError::FixtureError(_) => ScrapeErrorKind::FixtureError,
+            ScrapeError::ModelSinkError(_) => ScrapeErrorKind::ModelSinkError,
+            ScrapeError::CheckpointError(_) => ScrapeErrorKind::CheckpointError,
+            ScrapeError::TestError => ScrapeErrorKind::TestError,
+            ScrapeError::WebdriverConnectionError(_) => ScrapeErrorKind::WebdriverConnectionError,
+            ScrapeError::WebdriverCommandError(_) => ScrapeErrorKind::WebdriverCommandError,
+        }
+    }
+
+    /// Errors stopping the pipeline execution regardless of the flow control, nested pipelines propagate them to the
+    /// enclosing ones
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            ScrapeError::WebdriverCommandError(_) | ScrapeError::ModelSinkError(_) | ScrapeError::FixtureError(_)
+        )
+    }
+}
+
 impl Display for ScrapeError {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
         match self {
@@ -146,10 +363,22 @@ impl Display for ScrapeError {
                 write!(fmt, "failed to resolve value")
             }
 
+            ScrapeError::ValueTransformError => {
+                write!(fmt, "failed to transform value")
+            }
+
+            ScrapeError::ValueConversionError(value_type) => {
+                write!(fmt, "failed to convert value to {} type", value_type)
+            }
+
             ScrapeError::ElementQueryEmptyResult => {
                 write!(fmt, "element query empty result")
             }
 
+            ScrapeError::WaitTimeout => {
+                write!(fmt, "element wait condition timeout")
+            }
+
             ScrapeError::MissingElement => {
                 write!(fmt, "required element is missing in the pipeline execution context")
             }
@@ -166,10 +395,59 @@ impl Display for ScrapeError {
                 write!(fmt, "missing specified pipeline stage")
             }
 
+            ScrapeError::InvalidPipeline(errors) => {
+                let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+                write!(fmt, "invalid pipeline: {}", errors.join("; "))
+            }
+
             ScrapeError::SetModelAttributeError => {
                 write!(fmt, "failed to populate model attribute")
             }
 
+            ScrapeError::SetContextValueError => {
+                write!(fmt, "failed to set context value")
+            }
+
+            ScrapeError::AssertionFailed(message) => {
+                write!(fmt, "assertion failed: {}", message)
+            }
+
+            ScrapeError::InvalidCondition(error) => {
+                write!(fmt, "invalid condition: {}", error)
+            }
+
+            ScrapeError::ScriptError(error) => {
+                write!(fmt, "script execution error: {}", error)
+            }
+
+            ScrapeError::ScreenshotError(error) => {
+                write!(fmt, "failed to save screenshot: {}", error)
+            }
+
+            ScrapeError::PageLoadError(error) => {
+                write!(fmt, "failed to load page: {}", error)
+            }
+
+            ScrapeError::InvalidQuery(query) => {
+                write!(fmt, "invalid element query: {}", query)
+            }
+
+            ScrapeError::UnsupportedOperation(error) => {
+                write!(fmt, "{}", error)
+            }
+
+            ScrapeError::FixtureError(error) => {
+                write!(fmt, "fixture error: {}", error)
+            }
+
+            ScrapeError::ModelSinkError(error) => {
+                write!(fmt, "failed to store model: {}", error)
+            }
+
+            ScrapeError::CheckpointError(error) => {
+                write!(fmt, "checkpoint error: {}", error)
+            }
+
             ScrapeError::TestError => {
                 write!(fmt, "test error")
             }
@@ -190,16 +468,22 @@ impl Error for ScrapeError {}
 #[cfg(test)]
 mod test {
     use crate::{
-        action::{OpenUrl, ScrapeAction, TestError, TestSuccess},
-        client::MockScrapeClient,
-        pipeline::{ScrapeContext, ScrapeError, ScrapePipeline},
+        action::{OpenUrl, QueryElement, ScrapeAction, Selector, TestError, TestSuccess},
+        checkpoint::Checkpoint,
+        client::{MockScrapeClient, BLANK_URL},
+        element::mock_element,
+        pipeline::{ScrapeContext, ScrapeError, ScrapeErrorKind, ScrapePipeline},
         scrapman::Scrapman,
+        sink::ChannelSink,
         stage::{FlowControl, ScrapeStage},
         value::Value,
         StoreModel,
     };
+    use fantoccini::error::CmdError;
     use futures::future;
     use mockall::predicate;
+    use serde_json::json;
+    use std::{env, fs};
 
     #[tokio::test]
     async fn test_plain_pipeline() {
@@ -244,6 +528,171 @@ mod test {
         assert_eq!(0, ctx.models.len());
     }
 
+    #[tokio::test]
+    async fn test_error_kind_flow() {
+        let pipeline = ScrapePipeline::default()
+            .push(
+                ScrapeStage::from(TestError)
+                    .on_error_kind(ScrapeErrorKind::MissingElement, FlowControl::Quit)
+                    .on_error_kind(ScrapeErrorKind::TestError, FlowControl::goto("Store"))
+                    .on_any_error(FlowControl::Quit),
+            )
+            .push(ScrapeStage::from(TestSuccess).on_complete(FlowControl::Quit))
+            .push(ScrapeStage::from(StoreModel).with_name("Store"));
+
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_disconnect()
+            .times(1)
+            .returning(|| Box::pin(future::ok(())));
+
+        let scrapman = Scrapman::new("");
+        let result = scrapman.launch_with_client(pipeline, None, client).await;
+        assert_eq!(true, result.is_ok());
+
+        let ctx = result.unwrap();
+        assert_eq!(1, ctx.models.len());
+    }
+
+    #[tokio::test]
+    async fn test_failure_capture() {
+        let directory = env::temp_dir().join("scrapman-test-failure-capture");
+        let pipeline = ScrapePipeline::default()
+            .push(ScrapeStage::from(TestError).on_error_kind(ScrapeErrorKind::TestError, FlowControl::Continue))
+            .push(ScrapeStage::from(TestError).with_name("Cards / Page"));
+
+        // Only the stage without the error kind specific flow control is captured
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_screenshot()
+            .times(1)
+            .returning(|| Box::pin(future::ok(vec![137, 80, 78, 71])));
+        client
+            .expect_source()
+            .times(1)
+            .returning(|| Box::pin(future::ok("<html></html>".into())));
+
+        let mut context = ScrapeContext::new(client, None).with_error_capture(&directory);
+        assert_eq!(true, pipeline.execute(&mut context).await.is_ok());
+
+        let mut files: Vec<_> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(2, files.len());
+        assert_eq!(true, files[0].ends_with("-Cards___Page.html"));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bounded_repeat() {
+        let pipeline = ScrapePipeline::default()
+            .push(
+                ScrapeStage::from(StoreModel)
+                    .with_name("Store")
+                    .on_complete(FlowControl::repeat().with_max_attempts(3, FlowControl::goto("Finish"))),
+            )
+            .push(StoreModel)
+            .push(ScrapeStage::from(TestSuccess).with_name("Finish"));
+
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_disconnect()
+            .times(1)
+            .returning(|| Box::pin(future::ok(())));
+
+        let scrapman = Scrapman::new("");
+        let result = scrapman.launch_with_client(pipeline, None, client).await;
+        assert_eq!(true, result.is_ok());
+
+        let ctx = result.unwrap();
+        assert_eq!(3, ctx.models.len());
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_repeat() {
+        let pipeline = ScrapePipeline::default().push(
+            ScrapeStage::from(StoreModel)
+                .checkpoint()
+                .on_complete(FlowControl::repeat().with_max_attempts(3, FlowControl::Continue)),
+        );
+
+        // State is captured before the first attempt only
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_current_url()
+            .times(1)
+            .returning(|| Box::pin(future::ok(BLANK_URL.to_owned())));
+
+        let path = env::temp_dir().join("scrapman-test-checkpoint-repeat.json");
+        let mut context = ScrapeContext::new(client, None).with_checkpoint(&path);
+        assert_eq!(true, pipeline.execute_from(&mut context, 0).await.is_ok());
+        assert_eq!(3, context.models.len());
+        assert_eq!(true, Checkpoint::load(&path).unwrap().models.is_empty());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_nested_command_error() {
+        let pipeline = ScrapePipeline::default().push(TestSuccess).push(
+            QueryElement::global(Selector::Css, Value::constant("article"))
+                .for_each(ScrapePipeline::default().push(OpenUrl::new(Value::constant("http://localhost")))),
+        );
+
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_find_all()
+            .times(1)
+            .returning(|_| Box::pin(future::ok(vec![mock_element("First"), mock_element("Second")])));
+        client.expect_goto().times(1).returning(|_| {
+            let error = CmdError::NotW3C(json!("session deleted"));
+            Box::pin(future::err(ScrapeError::WebdriverCommandError(error)))
+        });
+
+        let mut context = ScrapeContext::new(client, None);
+        let result = pipeline.execute(&mut context).await;
+        assert_eq!(true, matches!(result, Err(ScrapeError::WebdriverCommandError(_))));
+        assert_eq!(Some(1), context.failed_stage);
+    }
+
+    #[tokio::test]
+    async fn test_model_sink() {
+        let pipeline = ScrapePipeline::default().push(StoreModel).push(StoreModel);
+
+        let mut client = MockScrapeClient::new();
+        client
+            .expect_disconnect()
+            .times(1)
+            .returning(|| Box::pin(future::ok(())));
+
+        let (sink, mut receiver) = ChannelSink::new(2);
+        let context = ScrapeContext::new(client, None).with_sink(sink);
+
+        let scrapman = Scrapman::new("");
+        let result = scrapman.launch_with_context(pipeline, context).await;
+        assert_eq!(true, result.is_ok());
+
+        let ctx = result.unwrap();
+        assert_eq!(0, ctx.models.len());
+        assert_eq!(true, receiver.recv().await.is_some());
+        assert_eq!(true, receiver.recv().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_pipeline() {
+        let pipeline =
+            ScrapePipeline::default().push(ScrapeStage::from(TestSuccess).on_complete(FlowControl::goto("Missing")));
+
+        let scrapman = Scrapman::new("");
+        let result = scrapman
+            .launch_with_client(pipeline, None, MockScrapeClient::new())
+            .await;
+        assert_eq!(true, matches!(result, Err(ScrapeError::InvalidPipeline(_))));
+    }
+
     #[tokio::test]
     async fn test_conditional_pipeline_1() {
         let result = test_conditional_pipeline(TestSuccess).await;
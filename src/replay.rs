@@ -0,0 +1,637 @@
+use crate::{
+    client::ScrapeClient,
+    element::{Locator, ScrapeElement},
+    pipeline::{ScrapeError, ScrapeErrorKind},
+    value::JsonValue,
+};
+use async_trait::async_trait;
+use fantoccini::error::CmdError;
+use log::warn;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{Debug, Formatter, Result as FormatResult},
+    fs::{read_to_string, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Client or element call, elements are identified by the order they were found in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "call")]
+pub enum Call {
+    Goto {
+        url: String,
+    },
+    FindAll {
+        element: Option<usize>,
+        strategy: String,
+        query: String,
+    },
+    Execute {
+        script: String,
+        args: Vec<JsonValue>,
+        asynchronous: bool,
+    },
+    Screenshot {
+        element: Option<usize>,
+    },
+    Source,
+    CurrentUrl,
+    PerformActions {
+        actions: Vec<JsonValue>,
+    },
+    Text {
+        element: usize,
+    },
+    Attr {
+        element: usize,
+        name: String,
+    },
+    Click {
+        element: usize,
+    },
+    SendKeys {
+        element: usize,
+        text: String,
+    },
+    Disconnect,
+}
+
+impl Call {
+    fn find_all(element: Option<usize>, search: Locator<'_>) -> Self {
+        let (strategy, query) = match search {
+            Locator::Css(query) => ("Css", query),
+            Locator::Id(query) => ("Id", query),
+            Locator::LinkText(query) => ("LinkText", query),
+            Locator::XPath(query) => ("XPath", query),
+        };
+
+        Call::FindAll {
+            element,
+            strategy: strategy.to_owned(),
+            query: query.to_owned(),
+        }
+    }
+
+    /// Calls are matched by their serialized representation
+    fn key(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedError {
+    pub kind: ScrapeErrorKind,
+    pub message: String,
+}
+
+impl From<&ScrapeError> for RecordedError {
+    fn from(error: &ScrapeError) -> Self {
+        let message = match error {
+            ScrapeError::ScriptError(message)
+            | ScrapeError::ScreenshotError(message)
+            | ScrapeError::PageLoadError(message)
+            | ScrapeError::InvalidQuery(message)
+            | ScrapeError::UnsupportedOperation(message)
+            | ScrapeError::ModelSinkError(message)
+            | ScrapeError::FixtureError(message) => message.clone(),
+            error => error.to_string(),
+        };
+
+        RecordedError {
+            kind: error.kind(),
+            message,
+        }
+    }
+}
+
+impl From<RecordedError> for ScrapeError {
+    /// Errors without a message are restored as is, any other errors are restored as webdriver command errors
+    fn from(error: RecordedError) -> Self {
+        match error.kind {
+            ScrapeErrorKind::ElementQueryEmptyResult => ScrapeError::ElementQueryEmptyResult,
+            ScrapeErrorKind::MissingElement => ScrapeError::MissingElement,
+            ScrapeErrorKind::ScriptError => ScrapeError::ScriptError(error.message),
+            ScrapeErrorKind::ScreenshotError => ScrapeError::ScreenshotError(error.message),
+            ScrapeErrorKind::PageLoadError => ScrapeError::PageLoadError(error.message),
+            ScrapeErrorKind::InvalidQuery => ScrapeError::InvalidQuery(error.message),
+            ScrapeErrorKind::UnsupportedOperation => ScrapeError::UnsupportedOperation(error.message),
+            ScrapeErrorKind::FixtureError => ScrapeError::FixtureError(error.message),
+            _ => ScrapeError::WebdriverCommandError(CmdError::NotW3C(JsonValue::String(error.message))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    #[serde(flatten)]
+    pub call: Call,
+    pub result: Result<JsonValue, RecordedError>,
+}
+
+/// Calls made during a live pipeline execution, along with their results
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Recording {
+    pub interactions: Vec<Interaction>,
+    /// Script references of the found elements, indexed by the element id
+    pub references: Vec<Option<JsonValue>>,
+}
+
+impl Recording {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ScrapeError> {
+        let source = read_to_string(path).map_err(fixture_error)?;
+        serde_json::from_str(&source).map_err(fixture_error)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ScrapeError> {
+        let file = File::create(path).map_err(fixture_error)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self).map_err(fixture_error)
+    }
+}
+
+/// Client wrapper recording every client and element call, the recording is saved to the fixture file on disconnect,
+/// or once the client is dropped without being disconnected, e.g. after a failed pipeline execution
+pub struct RecordingClient<C: ScrapeClient> {
+    inner: C,
+    path: PathBuf,
+    recording: Arc<Mutex<Recording>>,
+    /// Number of the interactions in the last saved recording
+    saved: usize,
+}
+
+impl<C: ScrapeClient> RecordingClient<C> {
+    pub fn new<P: Into<PathBuf>>(inner: C, path: P) -> Self {
+        RecordingClient {
+            inner,
+            path: path.into(),
+            recording: Arc::default(),
+            saved: 0,
+        }
+    }
+
+    fn save(&mut self) -> Result<(), ScrapeError> {
+        let recording = lock(&self.recording);
+        recording.save(&self.path)?;
+        self.saved = recording.interactions.len();
+        Ok(())
+    }
+}
+
+impl<C: ScrapeClient> Drop for RecordingClient<C> {
+    fn drop(&mut self) {
+        if lock(&self.recording).interactions.len() != self.saved {
+            if let Err(error) = self.save() {
+                warn!("Failed to save the recording to {}: {}", self.path.display(), error);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ScrapeClient> ScrapeClient for RecordingClient<C> {
+    async fn goto(&mut self, url: &str) -> Result<(), ScrapeError> {
+        let result = self.inner.goto(url).await;
+        record(&self.recording, Call::Goto { url: url.to_owned() }, &result);
+        result
+    }
+
+    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+        let result = self.inner.find_all(search).await;
+        record_elements(&self.recording, Call::find_all(None, search), result)
+    }
+
+    async fn execute(&mut self, script: &str, args: Vec<JsonValue>) -> Result<JsonValue, ScrapeError> {
+        let call = Call::Execute {
+            script: script.to_owned(),
+            args: args.clone(),
+            asynchronous: false,
+        };
+
+        let result = self.inner.execute(script, args).await;
+        record(&self.recording, call, &result);
+        result
+    }
+
+    async fn execute_async(&mut self, script: &str, args: Vec<JsonValue>) -> Result<JsonValue, ScrapeError> {
+        let call = Call::Execute {
+            script: script.to_owned(),
+            args: args.clone(),
+            asynchronous: true,
+        };
+
+        let result = self.inner.execute_async(script, args).await;
+        record(&self.recording, call, &result);
+        result
+    }
+
+    async fn screenshot(&mut self) -> Result<Vec<u8>, ScrapeError> {
+        let result = self.inner.screenshot().await;
+        record(&self.recording, Call::Screenshot { element: None }, &result);
+        result
+    }
+
+    async fn source(&mut self) -> Result<String, ScrapeError> {
+        let result = self.inner.source().await;
+        record(&self.recording, Call::Source, &result);
+        result
+    }
+
+    async fn current_url(&mut self) -> Result<String, ScrapeError> {
+        let result = self.inner.current_url().await;
+        record(&self.recording, Call::CurrentUrl, &result);
+        result
+    }
+
+    async fn perform_actions(&mut self, actions: Vec<JsonValue>) -> Result<(), ScrapeError> {
+        let call = Call::PerformActions {
+            actions: actions.clone(),
+        };
+
+        let result = self.inner.perform_actions(actions).await;
+        record(&self.recording, call, &result);
+        result
+    }
+
+    async fn disconnect(&mut self) -> Result<(), ScrapeError> {
+        let result = self.inner.disconnect().await;
+        record(&self.recording, Call::Disconnect, &result);
+        self.save()?;
+        result
+    }
+}
+
+#[derive(Clone)]
+struct RecordingElement {
+    inner: Box<dyn ScrapeElement>,
+    id: usize,
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl Debug for RecordingElement {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "RecordingElement({}, {:?})", self.id, self.inner)
+    }
+}
+
+#[async_trait]
+impl ScrapeElement for RecordingElement {
+    async fn text(&mut self) -> Result<String, ScrapeError> {
+        let result = self.inner.text().await;
+        record(&self.recording, Call::Text { element: self.id }, &result);
+        result
+    }
+
+    async fn attr(&mut self, name: &str) -> Result<Option<String>, ScrapeError> {
+        let call = Call::Attr {
+            element: self.id,
+            name: name.to_owned(),
+        };
+
+        let result = self.inner.attr(name).await;
+        record(&self.recording, call, &result);
+        result
+    }
+
+    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+        let result = self.inner.find_all(search).await;
+        record_elements(&self.recording, Call::find_all(Some(self.id), search), result)
+    }
+
+    async fn click(&mut self) -> Result<(), ScrapeError> {
+        let result = self.inner.click().await;
+        record(&self.recording, Call::Click { element: self.id }, &result);
+        result
+    }
+
+    async fn send_keys(&mut self, text: &str) -> Result<(), ScrapeError> {
+        let call = Call::SendKeys {
+            element: self.id,
+            text: text.to_owned(),
+        };
+
+        let result = self.inner.send_keys(text).await;
+        record(&self.recording, call, &result);
+        result
+    }
+
+    async fn screenshot(&mut self) -> Result<Vec<u8>, ScrapeError> {
+        let result = self.inner.screenshot().await;
+        record(&self.recording, Call::Screenshot { element: Some(self.id) }, &result);
+        result
+    }
+
+    fn reference(&self) -> Option<JsonValue> {
+        self.inner.reference()
+    }
+
+    fn clone_element(&self) -> Box<dyn ScrapeElement> {
+        Box::new(self.clone())
+    }
+}
+
+fn record<T: Serialize>(recording: &Mutex<Recording>, call: Call, result: &Result<T, ScrapeError>) {
+    let result = match result {
+        Ok(value) => Ok(serde_json::to_value(value).unwrap_or_default()),
+        Err(error) => Err(RecordedError::from(error)),
+    };
+
+    lock(recording).interactions.push(Interaction { call, result });
+}
+
+/// Found elements are recorded as ids, and are wrapped to record their own calls
+fn record_elements(
+    recording: &Arc<Mutex<Recording>>,
+    call: Call,
+    result: Result<Vec<Box<dyn ScrapeElement>>, ScrapeError>,
+) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+    let elements = match result {
+        Ok(elements) => elements,
+        Err(error) => {
+            let result = Err(RecordedError::from(&error));
+            lock(recording).interactions.push(Interaction { call, result });
+            return Err(error);
+        }
+    };
+
+    let mut state = lock(recording);
+    let elements = elements
+        .into_iter()
+        .map(|inner| {
+            let id = state.references.len();
+            state.references.push(inner.reference());
+
+            let element: Box<dyn ScrapeElement> = Box::new(RecordingElement {
+                inner,
+                id,
+                recording: recording.clone(),
+            });
+            element
+        })
+        .collect::<Vec<_>>();
+
+    let ids = (state.references.len() - elements.len()..state.references.len()).collect::<Vec<_>>();
+    state.interactions.push(Interaction {
+        call,
+        result: Ok(JsonValue::from(ids)),
+    });
+
+    Ok(elements)
+}
+
+/// Client serving the recorded call results, repeated calls are served in the recorded order
+#[derive(Clone)]
+pub struct ReplayClient {
+    state: Arc<Mutex<ReplayState>>,
+}
+
+struct ReplayState {
+    results: HashMap<String, VecDeque<Result<JsonValue, RecordedError>>>,
+    references: Vec<Option<JsonValue>>,
+}
+
+impl ReplayClient {
+    pub fn new(recording: Recording) -> Self {
+        let mut results = HashMap::<_, VecDeque<_>>::new();
+        for interaction in recording.interactions {
+            results
+                .entry(interaction.call.key())
+                .or_default()
+                .push_back(interaction.result);
+        }
+
+        ReplayClient {
+            state: Arc::new(Mutex::new(ReplayState {
+                results,
+                references: recording.references,
+            })),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ScrapeError> {
+        Recording::load(path).map(ReplayClient::new)
+    }
+
+    /// Recorded result of the call, calls missing in the recording fail with the fixture error
+    fn replay<T: DeserializeOwned>(&self, call: Call) -> Result<T, ScrapeError> {
+        let result = lock(&self.state)
+            .results
+            .get_mut(&call.key())
+            .and_then(VecDeque::pop_front)
+            .ok_or_else(|| ScrapeError::FixtureError(format!("no recorded result for {:?}", call)))?;
+
+        result
+            .map_err(ScrapeError::from)
+            .and_then(|value| serde_json::from_value(value).map_err(fixture_error))
+    }
+
+    fn replay_elements(&self, call: Call) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+        let ids: Vec<usize> = self.replay(call)?;
+        let elements = ids
+            .into_iter()
+            .map(|id| {
+                let element: Box<dyn ScrapeElement> = Box::new(ReplayElement {
+                    client: self.clone(),
+                    id,
+                });
+                element
+            })
+            .collect();
+
+        Ok(elements)
+    }
+}
+
+#[async_trait]
+impl ScrapeClient for ReplayClient {
+    async fn goto(&mut self, url: &str) -> Result<(), ScrapeError> {
+        self.replay(Call::Goto { url: url.to_owned() })
+    }
+
+    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+        self.replay_elements(Call::find_all(None, search))
+    }
+
+    async fn execute(&mut self, script: &str, args: Vec<JsonValue>) -> Result<JsonValue, ScrapeError> {
+        self.replay(Call::Execute {
+            script: script.to_owned(),
+            args,
+            asynchronous: false,
+        })
+    }
+
+    async fn execute_async(&mut self, script: &str, args: Vec<JsonValue>) -> Result<JsonValue, ScrapeError> {
+        self.replay(Call::Execute {
+            script: script.to_owned(),
+            args,
+            asynchronous: true,
+        })
+    }
+
+    async fn screenshot(&mut self) -> Result<Vec<u8>, ScrapeError> {
+        self.replay(Call::Screenshot { element: None })
+    }
+
+    async fn source(&mut self) -> Result<String, ScrapeError> {
+        self.replay(Call::Source)
+    }
+
+    async fn current_url(&mut self) -> Result<String, ScrapeError> {
+        self.replay(Call::CurrentUrl)
+    }
+
+    async fn perform_actions(&mut self, actions: Vec<JsonValue>) -> Result<(), ScrapeError> {
+        self.replay(Call::PerformActions { actions })
+    }
+
+    async fn disconnect(&mut self) -> Result<(), ScrapeError> {
+        self.replay(Call::Disconnect)
+    }
+}
+
+#[derive(Clone)]
+struct ReplayElement {
+    client: ReplayClient,
+    id: usize,
+}
+
+impl Debug for ReplayElement {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "ReplayElement({})", self.id)
+    }
+}
+
+#[async_trait]
+impl ScrapeElement for ReplayElement {
+    async fn text(&mut self) -> Result<String, ScrapeError> {
+        self.client.replay(Call::Text { element: self.id })
+    }
+
+    async fn attr(&mut self, name: &str) -> Result<Option<String>, ScrapeError> {
+        self.client.replay(Call::Attr {
+            element: self.id,
+            name: name.to_owned(),
+        })
+    }
+
+    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+        self.client.replay_elements(Call::find_all(Some(self.id), search))
+    }
+
+    async fn click(&mut self) -> Result<(), ScrapeError> {
+        self.client.replay(Call::Click { element: self.id })
+    }
+
+    async fn send_keys(&mut self, text: &str) -> Result<(), ScrapeError> {
+        self.client.replay(Call::SendKeys {
+            element: self.id,
+            text: text.to_owned(),
+        })
+    }
+
+    async fn screenshot(&mut self) -> Result<Vec<u8>, ScrapeError> {
+        self.client.replay(Call::Screenshot { element: Some(self.id) })
+    }
+
+    /// Recorded reference is returned, so the script calls are matched with the recorded ones
+    fn reference(&self) -> Option<JsonValue> {
+        lock(&self.client.state).references.get(self.id).cloned().flatten()
+    }
+
+    fn clone_element(&self) -> Box<dyn ScrapeElement> {
+        Box::new(self.clone())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn fixture_error<E: ToString>(error: E) -> ScrapeError {
+    ScrapeError::FixtureError(error.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{OpenUrl, QueryElement, Selector, SetModelAttribute, StoreModel},
+        client::{MockScrapeClient, ScrapeClient},
+        element::{mock_element, Locator},
+        pipeline::{ScrapeContext, ScrapeError, ScrapePipeline},
+        replay::{Recording, RecordingClient, ReplayClient},
+        value::Value,
+    };
+    use futures::future;
+    use serde_json::json;
+    use std::{env, fs};
+
+    fn pipeline(selector: &str) -> ScrapePipeline {
+        ScrapePipeline::default()
+            .push(OpenUrl::new(Value::constant("https://example.com")))
+            .push(
+                QueryElement::global(Selector::Css, Value::constant(selector)).for_each(
+                    ScrapePipeline::default()
+                        .push(SetModelAttribute::new("title", Value::ElementText))
+                        .push(StoreModel),
+                ),
+            )
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let mut client = MockScrapeClient::new();
+        client.expect_goto().returning(|_| Box::pin(future::ok(())));
+        client
+            .expect_find_all()
+            .returning(|_| Box::pin(future::ok(vec![mock_element("First"), mock_element("Second")])));
+        client.expect_disconnect().returning(|| Box::pin(future::ok(())));
+
+        let path = env::temp_dir().join("scrapman-test-recording.json");
+        let mut context = ScrapeContext::new(RecordingClient::new(client, &path), None);
+        pipeline("article").execute(&mut context).await.unwrap();
+        context.client.disconnect().await.unwrap();
+
+        let recording = Recording::load(&path).unwrap();
+        assert_eq!(5, recording.interactions.len());
+        assert_eq!(2, recording.references.len());
+
+        // Replay produces the same models without the live client
+        let mut context = ScrapeContext::new(ReplayClient::new(recording.clone()), None);
+        pipeline("article").execute(&mut context).await.unwrap();
+        assert_eq!(
+            vec![json!({"title": "First"}), json!({"title": "Second"})],
+            context.models
+        );
+
+        // Changed selector is missing in the recording, the execution is stopped regardless of the flow control
+        let mut context = ScrapeContext::new(ReplayClient::new(recording), None);
+        let result = pipeline("div.card").execute(&mut context).await;
+        assert_eq!(true, matches!(result, Err(ScrapeError::FixtureError(_))));
+        assert_eq!(true, context.models.is_empty());
+
+        let mut client = ReplayClient::load(&path).unwrap();
+        let result = client.find_all(Locator::Id("card")).await;
+        assert_eq!(true, matches!(result, Err(ScrapeError::FixtureError(_))));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_save_recording_on_drop() {
+        let mut client = MockScrapeClient::new();
+        client.expect_goto().returning(|_| Box::pin(future::ok(())));
+        client.expect_find_all().returning(|_| Box::pin(future::ok(Vec::new())));
+
+        // The client is never disconnected, the recording is saved once the context is dropped
+        let path = env::temp_dir().join("scrapman-test-dropped-recording.json");
+        let mut context = ScrapeContext::new(RecordingClient::new(client, &path), None);
+        pipeline("article").execute(&mut context).await.unwrap();
+        drop(context);
+
+        let recording = Recording::load(&path).unwrap();
+        assert_eq!(2, recording.interactions.len());
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -1,36 +1,146 @@
 use crate::{
+    capabilities::BrowserCapabilities,
+    checkpoint::Checkpoint,
     client::ScrapeClient,
     pipeline::{ScrapeContext, ScrapeError, ScrapePipeline},
+    sink::ModelSink,
+    validation::ValidationError,
     value::JsonValue,
+    webdriver::WebDriverClient,
 };
 use fantoccini::ClientBuilder;
-use log::info;
+use futures::future::Future;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+};
+use tokio::{
+    sync::mpsc::{channel, Receiver, Sender},
+    time::{sleep, Duration},
+};
 
 pub type ScrapeResult = Result<ScrapeContext, ScrapeError>;
 
+/// Result of the pipeline execution for one of the inputs launched with `Scrapman::launch_many`
+#[derive(Debug)]
+pub struct LaunchOutcome {
+    /// Position of the input values in the launched list
+    pub index: usize,
+    pub values: JsonValue,
+    pub result: Result<Vec<JsonValue>, ScrapeError>,
+}
+
+/// Pipeline runner, can be deserialized from the run configuration
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Scrapman {
+    #[serde(default = "default_webdriver_url")]
     webdriver_url: String,
+    #[serde(default)]
+    error_capture: Option<PathBuf>,
+    #[serde(default)]
+    checkpoint: Option<PathBuf>,
+    #[serde(default)]
+    capabilities: Option<BrowserCapabilities>,
+    #[serde(default)]
+    session_recovery: Option<SessionRecovery>,
+}
+
+/// Reopening of the WebDriver session once a command fails, e.g. the browser crashes. The pipeline execution is
+/// resumed from the failed top level stage, or the closest checkpoint stage preceding it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionRecovery {
+    /// Maximum number of sessions reopened within a single pipeline execution
+    pub max_reconnects: u32,
+    /// Delay in seconds before the session is reopened
+    #[serde(default)]
+    pub delay: Option<f64>,
+}
+
+impl SessionRecovery {
+    pub fn new(max_reconnects: u32) -> Self {
+        SessionRecovery {
+            max_reconnects,
+            delay: None,
+        }
+    }
+
+    pub fn with_delay(mut self, delay: f64) -> Self {
+        self.delay = Some(delay);
+        self
+    }
 }
 
 impl Scrapman {
     pub fn new<T: Into<String>>(webdriver_url: T) -> Self {
         Scrapman {
             webdriver_url: webdriver_url.into(),
+            error_capture: None,
+            checkpoint: None,
+            capabilities: None,
+            session_recovery: None,
         }
     }
 
+    pub fn with_webdriver_url<T: Into<String>>(mut self, webdriver_url: T) -> Self {
+        self.webdriver_url = webdriver_url.into();
+        self
+    }
+
+    /// Screenshot and page source are saved to the directory whenever a stage execution fails
+    pub fn with_error_capture<P: Into<PathBuf>>(mut self, directory: P) -> Self {
+        self.error_capture = Some(directory.into());
+        self
+    }
+
+    /// Context state is saved to the file before every top level checkpoint stage is executed, the execution can be
+    /// resumed from the file with `Scrapman::resume`
+    pub fn with_checkpoint<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.checkpoint = Some(path.into());
+        self
+    }
+
+    /// Capabilities requested for every WebDriver session opened by the runner
+    pub fn with_capabilities(mut self, capabilities: BrowserCapabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    pub fn with_session_recovery(mut self, recovery: SessionRecovery) -> Self {
+        self.session_recovery = Some(recovery);
+        self
+    }
+
     pub async fn launch<T>(&self, pipeline: ScrapePipeline, values: T) -> ScrapeResult
     where
         T: Into<Option<JsonValue>>,
     {
-        let client = ClientBuilder::native()
-            .connect(&self.webdriver_url)
+        let client = self.connect().await?;
+        let context = ScrapeContext::new(client, values);
+        self.launch_context(pipeline, context, self.session_recovery.as_ref())
             .await
-            .map_err(ScrapeError::WebdriverConnectionError)?;
+    }
 
-        self.launch_with_client(pipeline, values, client).await
+    /// Stored models are emitted to the sink as soon as they are stored by the pipeline
+    pub async fn launch_with_sink<Values, Sink>(
+        &self,
+        pipeline: ScrapePipeline,
+        values: Values,
+        sink: Sink,
+    ) -> ScrapeResult
+    where
+        Values: Into<Option<JsonValue>>,
+        Sink: ModelSink + 'static,
+    {
+        let client = self.connect().await?;
+        let context = ScrapeContext::new(client, values).with_sink(sink);
+        self.launch_context(pipeline, context, self.session_recovery.as_ref())
+            .await
     }
 
+    /// Session recovery is disabled, the runner can not reopen the sessions of an arbitrary client
     pub async fn launch_with_client<Values, Client>(
         &self,
         pipeline: ScrapePipeline,
@@ -41,10 +151,477 @@ impl Scrapman {
         Values: Into<Option<JsonValue>>,
         Client: ScrapeClient + 'static,
     {
-        let mut context = ScrapeContext::new(client, values);
-        info!("Launching pipeline execution");
-        pipeline.execute(&mut context).await?;
+        self.launch_with_context(pipeline, ScrapeContext::new(client, values))
+            .await
+    }
+
+    /// Session recovery is disabled, the runner can not reopen the sessions of an arbitrary client
+    pub async fn launch_with_context(&self, pipeline: ScrapePipeline, context: ScrapeContext) -> ScrapeResult {
+        self.launch_context(pipeline, context, None).await
+    }
+
+    /// Continues the pipeline execution from the saved checkpoint in a new session
+    pub async fn resume(&self, pipeline: ScrapePipeline, checkpoint: Checkpoint) -> ScrapeResult {
+        let client = self.connect().await?;
+        let context = ScrapeContext::new(client, None);
+        self.resume_context(pipeline, checkpoint, context, self.session_recovery.as_ref())
+            .await
+    }
+
+    /// Checkpoint state replaces the context values, model and models, the saved page is opened before the execution.
+    /// Session recovery is disabled, the runner can not reopen the sessions of an arbitrary client
+    pub async fn resume_with_context(
+        &self,
+        pipeline: ScrapePipeline,
+        checkpoint: Checkpoint,
+        context: ScrapeContext,
+    ) -> ScrapeResult {
+        self.resume_context(pipeline, checkpoint, context, None).await
+    }
+
+    async fn launch_context(
+        &self,
+        pipeline: ScrapePipeline,
+        mut context: ScrapeContext,
+        recovery: Option<&SessionRecovery>,
+    ) -> ScrapeResult {
+        validate(&pipeline)?;
+        self.execute(&pipeline, &mut context, 0, recovery, || self.connect())
+            .await?;
+        context.client.disconnect().await?;
+        Ok(context)
+    }
+
+    async fn resume_context(
+        &self,
+        pipeline: ScrapePipeline,
+        checkpoint: Checkpoint,
+        mut context: ScrapeContext,
+        recovery: Option<&SessionRecovery>,
+    ) -> ScrapeResult {
+        validate(&pipeline)?;
+        if checkpoint.stage >= pipeline.stages().len() {
+            return Err(ScrapeError::CheckpointError(format!(
+                "stage {} is missing in the pipeline",
+                checkpoint.stage
+            )));
+        }
+
+        info!("Resuming pipeline execution from stage {}", checkpoint.stage);
+        let start = checkpoint.restore(&mut context).await?;
+        self.execute(&pipeline, &mut context, start, recovery, || self.connect())
+            .await?;
         context.client.disconnect().await?;
         Ok(context)
     }
+
+    /// Runs the pipeline for every input values object over a pool of WebDriver sessions, at most `sessions` inputs
+    /// are processed concurrently. Outcomes are sent to the receiver in the order of completion
+    pub fn launch_many<I>(&self, pipeline: ScrapePipeline, values: I, sessions: usize) -> Receiver<LaunchOutcome>
+    where
+        I: IntoIterator<Item = JsonValue>,
+    {
+        let scrapman = self.clone();
+        self.launch_many_with(pipeline, values, sessions, move || {
+            let scrapman = scrapman.clone();
+            async move { scrapman.connect().await }
+        })
+    }
+
+    /// Same as `Scrapman::launch_many`, sessions are opened with the specified function, failed sessions are reopened
+    /// with it as well if the session recovery is enabled
+    pub fn launch_many_with<I, F, R, C>(
+        &self,
+        pipeline: ScrapePipeline,
+        values: I,
+        sessions: usize,
+        connect: F,
+    ) -> Receiver<LaunchOutcome>
+    where
+        I: IntoIterator<Item = JsonValue>,
+        F: Fn() -> R + Send + Sync + 'static,
+        R: Future<Output = Result<C, ScrapeError>> + Send,
+        C: ScrapeClient + 'static,
+    {
+        let inputs: VecDeque<_> = values.into_iter().enumerate().collect();
+        let (sender, receiver) = channel(inputs.len().max(1));
+
+        // Invalid pipeline is reported for every input, no sessions are opened
+        if let Err(ScrapeError::InvalidPipeline(errors)) = validate(&pipeline) {
+            for (index, values) in inputs {
+                let result = Err(ScrapeError::InvalidPipeline(errors.clone()));
+                let _ = sender.try_send(LaunchOutcome { index, values, result });
+            }
+
+            return receiver;
+        }
+
+        // Inputs would overwrite the checkpoints of each other
+        let mut scrapman = self.clone();
+        if scrapman.checkpoint.take().is_some() {
+            warn!("Checkpoints are not saved by the concurrent execution");
+        }
+
+        let inputs = Arc::new(Mutex::new(inputs));
+        let pipeline = Arc::new(pipeline);
+        let connect = Arc::new(connect);
+        for _ in 0..sessions.max(1) {
+            let worker = Worker {
+                scrapman: scrapman.clone(),
+                pipeline: pipeline.clone(),
+                inputs: inputs.clone(),
+                sender: sender.clone(),
+            };
+
+            let connect = connect.clone();
+            tokio::spawn(async move { worker.run(|| connect()).await });
+        }
+
+        receiver
+    }
+
+    /// Pipeline execution within the context, the client is not disconnected. Failed sessions are reopened with the
+    /// specified function if the recovery is passed
+    async fn execute<F, R, C>(
+        &self,
+        pipeline: &ScrapePipeline,
+        context: &mut ScrapeContext,
+        start: usize,
+        recovery: Option<&SessionRecovery>,
+        connect: F,
+    ) -> Result<(), ScrapeError>
+    where
+        F: Fn() -> R,
+        R: Future<Output = Result<C, ScrapeError>>,
+        C: ScrapeClient + 'static,
+    {
+        if context.error_capture.is_none() {
+            context.error_capture = self.error_capture.clone();
+        }
+
+        if context.checkpoint.is_none() {
+            context.checkpoint = self.checkpoint.clone();
+        }
+
+        info!("Launching pipeline execution");
+        let mut start = start;
+        let mut reconnects = 0;
+        loop {
+            context.failed_stage = None;
+            let error = match pipeline.execute_from(context, start).await {
+                Err(error @ ScrapeError::WebdriverCommandError(_)) => error,
+                result => break result?,
+            };
+
+            let recovery = match recovery {
+                Some(recovery) if reconnects < recovery.max_reconnects => recovery,
+                _ => return Err(error),
+            };
+
+            reconnects += 1;
+            warn!(
+                "Session failure: {}, reopening the session ({}/{})",
+                error, reconnects, recovery.max_reconnects
+            );
+
+            if let Err(error) = context.client.disconnect().await {
+                warn!("Failed to close the session: {}", error);
+            }
+
+            if let Some(delay) = recovery.delay {
+                sleep(Duration::from_secs_f64(delay)).await;
+            }
+
+            // Elements belong to the failed session, values and models are kept
+            context.client = Box::new(connect().await?);
+            context.current_element = None;
+            context.scoped_element = None;
+            start = pipeline.resume_position(context.failed_stage.unwrap_or(0));
+            info!("Resuming pipeline execution from stage {}", start);
+        }
+
+        if let Some(ref mut sink) = context.sink {
+            sink.flush().await?;
+        }
+
+        // Completed execution is never resumed
+        if let Some(ref path) = context.checkpoint {
+            Checkpoint::clear(path)?;
+        }
+
+        Ok(())
+    }
+
+    async fn connect(&self) -> Result<WebDriverClient, ScrapeError> {
+        let mut builder = ClientBuilder::native();
+        if let Some(ref capabilities) = self.capabilities {
+            builder.capabilities(capabilities.to_capabilities());
+        }
+
+        let client = builder
+            .connect(&self.webdriver_url)
+            .await
+            .map_err(ScrapeError::WebdriverConnectionError)?;
+
+        Ok(WebDriverClient::new(client, self.webdriver_url.as_str()))
+    }
+}
+
+impl Default for Scrapman {
+    fn default() -> Self {
+        Scrapman::new(default_webdriver_url())
+    }
+}
+
+fn default_webdriver_url() -> String {
+    "http://localhost:4444".to_owned()
+}
+
+/// Pipeline is validated before any work is done, warnings are only logged
+pub(crate) fn validate(pipeline: &ScrapePipeline) -> Result<(), ScrapeError> {
+    if let Err(errors) = pipeline.validate() {
+        let (warnings, errors): (Vec<_>, Vec<_>) = errors.into_iter().partition(ValidationError::is_warning);
+        for warning in warnings {
+            warn!("Pipeline validation: {}", warning);
+        }
+
+        if !errors.is_empty() {
+            return Err(ScrapeError::InvalidPipeline(errors));
+        }
+    }
+
+    Ok(())
+}
+
+/// Single session of the `Scrapman::launch_many` pool, takes the inputs from the shared queue until it is empty
+struct Worker {
+    scrapman: Scrapman,
+    pipeline: Arc<ScrapePipeline>,
+    inputs: Arc<Mutex<VecDeque<(usize, JsonValue)>>>,
+    sender: Sender<LaunchOutcome>,
+}
+
+impl Worker {
+    async fn run<F, R, C>(self, connect: F)
+    where
+        F: Fn() -> R,
+        R: Future<Output = Result<C, ScrapeError>>,
+        C: ScrapeClient + 'static,
+    {
+        let mut client: Option<Box<dyn ScrapeClient>> = None;
+        while let Some((index, values)) = self.next_input() {
+            let session = match client.take() {
+                Some(session) => session,
+                None => match connect().await {
+                    Ok(session) => Box::new(session),
+                    Err(error) => {
+                        self.send(index, values, Err(error)).await;
+                        continue;
+                    }
+                },
+            };
+
+            let mut context = ScrapeContext::with_client(session, values.clone());
+            let recovery = self.scrapman.session_recovery.as_ref();
+            let result = self
+                .scrapman
+                .execute(&self.pipeline, &mut context, 0, recovery, &connect)
+                .await;
+
+            // Session is reused for the following inputs, unless the webdriver command failed
+            let (mut session, models) = (context.client, context.models);
+            match result {
+                Err(ScrapeError::WebdriverCommandError(_)) => {
+                    let _ = session.disconnect().await;
+                }
+                _ => client = Some(session),
+            }
+
+            self.send(index, values, result.map(|_| models)).await;
+        }
+
+        if let Some(mut session) = client {
+            if let Err(error) = session.disconnect().await {
+                warn!("Failed to close the session: {}", error);
+            }
+        }
+    }
+
+    fn next_input(&self) -> Option<(usize, JsonValue)> {
+        self.inputs.lock().unwrap_or_else(PoisonError::into_inner).pop_front()
+    }
+
+    async fn send(&self, index: usize, values: JsonValue, result: Result<Vec<JsonValue>, ScrapeError>) {
+        if self.sender.send(LaunchOutcome { index, values, result }).await.is_err() {
+            warn!("Launch outcome receiver is closed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{OpenUrl, QueryElement, Selector, SetModelAttribute, StoreModel},
+        checkpoint::Checkpoint,
+        client::MockScrapeClient,
+        element::mock_element,
+        pipeline::{ScrapeContext, ScrapeError, ScrapePipeline},
+        scrapman::{Scrapman, SessionRecovery},
+        stage::ScrapeStage,
+        static_client::StaticClient,
+        value::Value,
+    };
+    use fantoccini::error::CmdError;
+    use futures::future;
+    use serde_json::json;
+    use std::{
+        env,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_launch_many() {
+        let pipeline = ScrapePipeline::default()
+            .push(OpenUrl::new(Value::context("url")))
+            .push(QueryElement::global(Selector::TagName, Value::constant("h1")))
+            .push(SetModelAttribute::new("title", Value::ElementText))
+            .push(StoreModel);
+
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+        let mut receiver = Scrapman::new("").launch_many_with(
+            pipeline,
+            (0..5).map(|idx| json!({ "url": format!("https://example.com/{}", idx) })),
+            2,
+            move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut client = StaticClient::new();
+                for idx in 0..5 {
+                    let html = format!("<h1>Page {}</h1>", idx);
+                    client = client.with_page(format!("https://example.com/{}", idx), html);
+                }
+                future::ok(client)
+            },
+        );
+
+        let mut outcomes = Vec::new();
+        while let Some(outcome) = receiver.recv().await {
+            outcomes.push(outcome);
+        }
+
+        outcomes.sort_by_key(|outcome| outcome.index);
+        assert_eq!(5, outcomes.len());
+        // Sessions are reused, the pool is never larger than requested
+        assert_eq!(true, connections.load(Ordering::SeqCst) <= 2);
+        for (idx, outcome) in outcomes.iter().enumerate() {
+            assert_eq!(idx, outcome.index);
+            assert_eq!(json!({ "url": format!("https://example.com/{}", idx) }), outcome.values);
+            assert_eq!(
+                vec![json!({ "title": format!("Page {}", idx) })],
+                *outcome.result.as_ref().unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_recovery() {
+        let pipeline = ScrapePipeline::default()
+            .push(StoreModel)
+            .push(ScrapeStage::from(OpenUrl::new(Value::constant("https://example.com"))).checkpoint())
+            .push(QueryElement::global(Selector::Css, Value::constant("h1")))
+            .push(SetModelAttribute::new("title", Value::ElementText))
+            .push(StoreModel);
+
+        // The first session fails on the element query, the second one completes the pipeline
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+        let scrapman = Scrapman::new("").with_session_recovery(SessionRecovery::new(1));
+        let mut receiver = scrapman.launch_many_with(pipeline, vec![json!({})], 1, move || {
+            let mut client = MockScrapeClient::new();
+            client.expect_goto().times(1).returning(|_| Box::pin(future::ok(())));
+            client
+                .expect_disconnect()
+                .times(1)
+                .returning(|| Box::pin(future::ok(())));
+
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                client.expect_find_all().times(1).returning(|_| {
+                    let error = CmdError::NotW3C(json!("session deleted"));
+                    Box::pin(future::err(ScrapeError::WebdriverCommandError(error)))
+                });
+            } else {
+                client
+                    .expect_find_all()
+                    .times(1)
+                    .returning(|_| Box::pin(future::ok(vec![mock_element("Title")])));
+            }
+
+            future::ok(client)
+        });
+
+        let outcome = receiver.recv().await.unwrap();
+        assert_eq!(2, connections.load(Ordering::SeqCst));
+        assert_eq!(vec![json!({}), json!({"title": "Title"})], outcome.result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_no_recovery_for_user_client() {
+        let pipeline = ScrapePipeline::default().push(QueryElement::global(Selector::Css, Value::constant("h1")));
+
+        // Runner never connects to the WebDriver server in place of the user client
+        let mut client = MockScrapeClient::new();
+        client.expect_find_all().times(1).returning(|_| {
+            let error = CmdError::NotW3C(json!("session deleted"));
+            Box::pin(future::err(ScrapeError::WebdriverCommandError(error)))
+        });
+        client.expect_disconnect().never();
+
+        let scrapman = Scrapman::new("").with_session_recovery(SessionRecovery::new(1));
+        let result = scrapman.launch_with_client(pipeline, None, client).await;
+        assert_eq!(true, matches!(result, Err(ScrapeError::WebdriverCommandError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_resume() {
+        let pipeline = || {
+            ScrapePipeline::default()
+                .push(OpenUrl::new(Value::context("url")))
+                .push(StoreModel)
+                .push(ScrapeStage::from(QueryElement::global(Selector::Css, Value::constant("h1"))).checkpoint())
+                .push(SetModelAttribute::new("title", Value::ElementText))
+                .push(StoreModel)
+        };
+
+        let url = "https://example.com/";
+        let client = || StaticClient::new().with_page(url, "<h1>Title</h1>");
+        let path = env::temp_dir().join("scrapman-test-checkpoint.json");
+        let scrapman = Scrapman::new("").with_checkpoint(&path);
+
+        // Checkpoint file is removed once the execution is completed
+        let context = ScrapeContext::new(client(), json!({ "url": url }));
+        assert_eq!(true, scrapman.launch_with_context(pipeline(), context).await.is_ok());
+        assert_eq!(false, path.exists());
+
+        // Interrupted execution leaves the checkpoint behind
+        let mut context = ScrapeContext::new(client(), json!({ "url": url })).with_checkpoint(&path);
+        assert_eq!(true, pipeline().execute_from(&mut context, 0).await.is_ok());
+
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        assert_eq!(2, checkpoint.stage);
+        assert_eq!(Some(url), checkpoint.url.as_deref());
+        assert_eq!(vec![json!({})], checkpoint.models);
+
+        let context = ScrapeContext::new(client(), None);
+        let context = scrapman
+            .resume_with_context(pipeline(), checkpoint, context)
+            .await
+            .unwrap();
+
+        assert_eq!(json!({ "url": url }), context.values);
+        assert_eq!(vec![json!({}), json!({"title": "Title"})], context.models);
+        assert_eq!(false, path.exists());
+    }
 }
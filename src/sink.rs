@@ -0,0 +1,264 @@
+use crate::{pipeline::ScrapeError, value::JsonValue};
+use async_trait::async_trait;
+use log::warn;
+use serde_json::Map;
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+/// Receiver of the models stored during the pipeline execution
+#[async_trait]
+pub trait ModelSink: Send + Sync {
+    async fn store(&mut self, model: JsonValue) -> Result<(), ScrapeError>;
+
+    async fn flush(&mut self) -> Result<(), ScrapeError> {
+        Ok(())
+    }
+}
+
+/// Writes every model as a separate JSON document line
+pub struct JsonLinesSink<W: Write + Send + Sync> {
+    writer: W,
+}
+
+impl<W: Write + Send + Sync> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        JsonLinesSink { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl JsonLinesSink<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, ScrapeError> {
+        let file = File::create(path).map_err(sink_error)?;
+        Ok(JsonLinesSink::new(BufWriter::new(file)))
+    }
+}
+
+#[async_trait]
+impl<W: Write + Send + Sync> ModelSink for JsonLinesSink<W> {
+    async fn store(&mut self, model: JsonValue) -> Result<(), ScrapeError> {
+        serde_json::to_writer(&mut self.writer, &model).map_err(sink_error)?;
+        writeln!(self.writer).map_err(sink_error)?;
+
+        // Every model is flushed immediately, so nothing is lost if the process is terminated
+        self.writer.flush().map_err(sink_error)
+    }
+
+    async fn flush(&mut self) -> Result<(), ScrapeError> {
+        self.writer.flush().map_err(sink_error)
+    }
+}
+
+/// Writes models as CSV records, nested model attributes are flattened into dot separated columns
+pub struct CsvSink<W: Write + Send + Sync> {
+    writer: csv::Writer<W>,
+    columns: Option<Vec<String>>,
+    header_written: bool,
+    dropped_columns: Option<HashSet<String>>,
+    strict: bool,
+}
+
+impl<W: Write + Send + Sync> CsvSink<W> {
+    /// Columns are taken from the first stored model. Attributes missing in the first model are dropped from the
+    /// following records with a warning, so models with different attributes require `CsvSink::with_columns`
+    pub fn new(writer: W) -> Self {
+        CsvSink {
+            writer: csv::Writer::from_writer(writer),
+            columns: None,
+            header_written: false,
+            dropped_columns: Some(HashSet::new()),
+            strict: false,
+        }
+    }
+
+    /// Only the listed columns are written, other model attributes are ignored
+    pub fn with_columns<T: Into<String>>(writer: W, columns: Vec<T>) -> Self {
+        CsvSink {
+            writer: csv::Writer::from_writer(writer),
+            columns: Some(columns.into_iter().map(Into::into).collect()),
+            header_written: false,
+            dropped_columns: None,
+            strict: false,
+        }
+    }
+
+    /// Models with attributes missing in the columns taken from the first model fail with `ModelSinkError` instead
+    /// of being written without them
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    pub fn into_inner(self) -> Result<W, ScrapeError> {
+        self.writer.into_inner().map_err(|error| sink_error(error.into_error()))
+    }
+}
+
+impl CsvSink<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, ScrapeError> {
+        let file = File::create(path).map_err(sink_error)?;
+        Ok(CsvSink::new(BufWriter::new(file)))
+    }
+
+    pub fn create_with_columns<P: AsRef<Path>, T: Into<String>>(path: P, columns: Vec<T>) -> Result<Self, ScrapeError> {
+        let file = File::create(path).map_err(sink_error)?;
+        Ok(CsvSink::with_columns(BufWriter::new(file), columns))
+    }
+}
+
+#[async_trait]
+impl<W: Write + Send + Sync> ModelSink for CsvSink<W> {
+    async fn store(&mut self, model: JsonValue) -> Result<(), ScrapeError> {
+        let mut attributes = Map::new();
+        flatten("", model, &mut attributes);
+
+        if self.columns.is_none() {
+            let columns = attributes.keys().cloned().collect::<Vec<_>>();
+            self.columns = Some(columns);
+        }
+
+        // Header is written along with the first record
+        let columns = self.columns.as_ref().cloned().unwrap_or_default();
+        if let Some(ref mut dropped_columns) = self.dropped_columns {
+            for column in attributes.keys().filter(|column| !columns.contains(column)) {
+                if self.strict {
+                    return Err(ScrapeError::ModelSinkError(format!(
+                        "attribute \"{}\" is not in the CSV columns taken from the first model",
+                        column
+                    )));
+                }
+
+                if dropped_columns.insert(column.clone()) {
+                    warn!(
+                        "Attribute \"{}\" is not in the CSV columns taken from the first model, and is dropped",
+                        column
+                    );
+                }
+            }
+        }
+
+        if !self.header_written {
+            self.writer.write_record(&columns).map_err(sink_error)?;
+            self.header_written = true;
+        }
+
+        let record = columns.iter().map(|column| match attributes.get(column) {
+            Some(JsonValue::String(value)) => value.clone(),
+            Some(JsonValue::Null) | None => String::new(),
+            Some(value) => value.to_string(),
+        });
+
+        self.writer.write_record(record).map_err(sink_error)?;
+        self.writer.flush().map_err(sink_error)
+    }
+
+    async fn flush(&mut self) -> Result<(), ScrapeError> {
+        self.writer.flush().map_err(sink_error)
+    }
+}
+
+fn flatten(prefix: &str, value: JsonValue, attributes: &mut Map<String, JsonValue>) {
+    match value {
+        JsonValue::Object(object) => {
+            for (key, value) in object {
+                let key = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+
+                flatten(&key, value, attributes);
+            }
+        }
+
+        value => {
+            attributes.insert(prefix.to_owned(), value);
+        }
+    }
+}
+
+/// Sends models to the channel receiver, the pipeline execution waits while the channel is full
+pub struct ChannelSink {
+    sender: Sender<JsonValue>,
+}
+
+impl ChannelSink {
+    pub fn new(capacity: usize) -> (Self, Receiver<JsonValue>) {
+        let (sender, receiver) = channel(capacity);
+        (ChannelSink { sender }, receiver)
+    }
+}
+
+#[async_trait]
+impl ModelSink for ChannelSink {
+    async fn store(&mut self, model: JsonValue) -> Result<(), ScrapeError> {
+        self.sender.send(model).await.map_err(sink_error)
+    }
+}
+
+fn sink_error<E: ToString>(error: E) -> ScrapeError {
+    ScrapeError::ModelSinkError(error.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sink::{ChannelSink, CsvSink, JsonLinesSink, ModelSink};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_json_lines_sink() {
+        let mut sink = JsonLinesSink::new(Vec::new());
+        sink.store(json!({"title": "A", "price": 1})).await.unwrap();
+        sink.store(json!({"title": "B", "price": null})).await.unwrap();
+
+        let output = String::from_utf8(sink.into_inner()).unwrap();
+        assert_eq!(
+            "{\"price\":1,\"title\":\"A\"}\n{\"price\":null,\"title\":\"B\"}\n",
+            output
+        );
+    }
+
+    #[tokio::test]
+    async fn test_csv_sink() {
+        let mut sink = CsvSink::new(Vec::new());
+        sink.store(json!({"title": "A, B", "price": 1, "address": {"city": "Moscow"}}))
+            .await
+            .unwrap();
+        sink.store(json!({"title": "C", "rooms": 2})).await.unwrap();
+
+        let output = String::from_utf8(sink.into_inner().unwrap()).unwrap();
+        assert_eq!("address.city,price,title\nMoscow,1,\"A, B\"\n,,C\n", output);
+    }
+
+    #[tokio::test]
+    async fn test_csv_sink_columns() {
+        let mut sink = CsvSink::with_columns(Vec::new(), vec!["title", "rooms"]);
+        sink.store(json!({"title": "A", "price": 1})).await.unwrap();
+        sink.store(json!({"title": "C", "rooms": 2})).await.unwrap();
+
+        let output = String::from_utf8(sink.into_inner().unwrap()).unwrap();
+        assert_eq!("title,rooms\nA,\nC,2\n", output);
+
+        let mut sink = CsvSink::new(Vec::new()).strict();
+        sink.store(json!({"title": "A"})).await.unwrap();
+        assert_eq!(true, sink.store(json!({"title": "B", "rooms": 2})).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_channel_sink() {
+        let (mut sink, mut receiver) = ChannelSink::new(2);
+        sink.store(json!({"title": "A"})).await.unwrap();
+        assert_eq!(Some(json!({"title": "A"})), receiver.recv().await);
+
+        drop(receiver);
+        assert_eq!(true, sink.store(json!({"title": "B"})).await.is_err());
+    }
+}
@@ -1,12 +1,82 @@
-use crate::action::ScrapeAction;
+use crate::{action::ScrapeAction, pipeline::ScrapeErrorKind};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Upper bound of the computed repeat delay in seconds, unbounded backoff stops growing at this value
+pub const MAX_REPEAT_DELAY: f64 = 3600.0;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum Backoff {
+    Linear { increment: f64 },
+    Exponential { factor: f64, max_delay: Option<f64> },
+}
+
+impl Backoff {
+    pub fn linear(increment: f64) -> Self {
+        Backoff::Linear { increment }
+    }
+
+    pub fn exponential(factor: f64) -> Self {
+        Backoff::Exponential {
+            factor,
+            max_delay: None,
+        }
+    }
+
+    /// Delay before the specified repeat, the first repeat has number 1. The delay is capped with `MAX_REPEAT_DELAY`
+    pub fn delay(&self, delay: f64, repeat: u32) -> f64 {
+        let steps = repeat.saturating_sub(1);
+        let delay = match self {
+            Backoff::Linear { increment } => delay + increment * steps as f64,
+            Backoff::Exponential { factor, max_delay } => {
+                let delay = delay * factor.powi(steps.min(i32::MAX as u32) as i32);
+                match max_delay {
+                    Some(max_delay) => delay.min(*max_delay),
+                    None => delay,
+                }
+            }
+        };
+
+        clamp_delay(delay)
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        match self {
+            Backoff::Linear { increment } => vec![*increment],
+            Backoff::Exponential { factor, max_delay } => vec![*factor].into_iter().chain(*max_delay).collect(),
+        }
+    }
+}
+
+/// Delays and backoff parameters must be finite non-negative numbers of seconds
+pub(crate) fn is_valid_delay(delay: f64) -> bool {
+    delay.is_finite() && delay >= 0.0
+}
+
+/// Delay in the range from zero to `MAX_REPEAT_DELAY`, invalid delays are not waited for
+pub(crate) fn clamp_delay(delay: f64) -> f64 {
+    if delay.is_nan() {
+        0.0
+    } else {
+        delay.clamp(0.0, MAX_REPEAT_DELAY)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub enum FlowControl {
+    #[default]
     Continue,
     Quit,
     Goto(String),
-    Repeat { delay: Option<f64> },
+    Repeat {
+        delay: Option<f64>,
+        #[serde(default)]
+        backoff: Option<Backoff>,
+        #[serde(default)]
+        max_attempts: Option<u32>,
+        #[serde(default)]
+        fallback: Option<Box<FlowControl>>,
+    },
 }
 
 impl FlowControl {
@@ -15,20 +85,82 @@ impl FlowControl {
     }
 
     pub fn repeat() -> Self {
-        FlowControl::Repeat { delay: None }
+        FlowControl::Repeat {
+            delay: None,
+            backoff: None,
+            max_attempts: None,
+            fallback: None,
+        }
     }
 
     pub fn repeat_with_delay(delay: f64) -> Self {
-        FlowControl::Repeat { delay: Some(delay) }
+        FlowControl::Repeat {
+            delay: Some(delay),
+            backoff: None,
+            max_attempts: None,
+            fallback: None,
+        }
+    }
+
+    /// Limits the stage executions in a row, the fallback flow is applied once the attempts are exhausted
+    pub fn with_max_attempts(self, max_attempts: u32, fallback: FlowControl) -> Self {
+        match self {
+            FlowControl::Repeat { delay, backoff, .. } => FlowControl::Repeat {
+                delay,
+                backoff,
+                max_attempts: Some(max_attempts),
+                fallback: Some(Box::new(fallback)),
+            },
+            flow => flow,
+        }
+    }
+
+    /// Repeat delay and backoff parameters are finite non-negative numbers
+    pub(crate) fn has_valid_delays(&self) -> bool {
+        match self {
+            FlowControl::Repeat { delay, backoff, .. } => delay
+                .iter()
+                .copied()
+                .chain(backoff.iter().flat_map(Backoff::parameters))
+                .all(is_valid_delay),
+            _ => true,
+        }
+    }
+
+    /// Increases the repeat delay with every attempt
+    pub fn with_backoff(self, backoff: Backoff) -> Self {
+        match self {
+            FlowControl::Repeat {
+                delay,
+                max_attempts,
+                fallback,
+                ..
+            } => FlowControl::Repeat {
+                delay,
+                backoff: Some(backoff),
+                max_attempts,
+                fallback,
+            },
+            flow => flow,
+        }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ScrapeStage {
+    #[serde(default)]
     pub name: Option<String>,
     pub action: Box<dyn ScrapeAction>,
+    #[serde(default)]
     pub on_complete: FlowControl,
+    #[serde(default)]
     pub on_error: FlowControl,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub on_error_kind: HashMap<ScrapeErrorKind, FlowControl>,
+    /// Context state is saved before the stage is executed, the execution is resumed from the stage after the session
+    /// recovery
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub checkpoint: bool,
 }
 
 impl ScrapeStage {
@@ -46,6 +178,23 @@ impl ScrapeStage {
         self.on_error = on_error;
         self
     }
+
+    pub fn on_error_kind(mut self, kind: ScrapeErrorKind, on_error: FlowControl) -> Self {
+        self.on_error_kind.insert(kind, on_error);
+        self
+    }
+
+    /// Marks the stage as the resume point of the following stages, e.g. the page opening. Elements found by the
+    /// previous stages are not available once the execution is resumed
+    pub fn checkpoint(mut self) -> Self {
+        self.checkpoint = true;
+        self
+    }
+
+    /// Flow control for the specified error kind, falls back to the "on any error" branch
+    pub fn error_flow(&self, kind: ScrapeErrorKind) -> &FlowControl {
+        self.on_error_kind.get(&kind).unwrap_or(&self.on_error)
+    }
 }
 
 impl<Action> From<Action> for ScrapeStage
@@ -58,6 +207,8 @@ where
             action: Box::new(action),
             on_complete: FlowControl::Continue,
             on_error: FlowControl::Continue,
+            on_error_kind: HashMap::new(),
+            checkpoint: false,
         }
     }
 }
@@ -73,6 +224,47 @@ where
             action: Box::new(self.1),
             on_complete: FlowControl::Continue,
             on_error: FlowControl::Continue,
+            on_error_kind: HashMap::new(),
+            checkpoint: false,
         }
     }
 }
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[cfg(test)]
+mod test {
+    use crate::stage::{Backoff, MAX_REPEAT_DELAY};
+
+    #[test]
+    fn test_backoff() {
+        let backoff = Backoff::linear(0.5);
+        assert_eq!(
+            vec![1.0, 1.5, 2.0],
+            (1..=3).map(|x| backoff.delay(1.0, x)).collect::<Vec<_>>()
+        );
+
+        let backoff = Backoff::exponential(2.0);
+        assert_eq!(
+            vec![0.5, 1.0, 2.0],
+            (1..=3).map(|x| backoff.delay(0.5, x)).collect::<Vec<_>>()
+        );
+
+        let backoff = Backoff::Exponential {
+            factor: 2.0,
+            max_delay: Some(1.5),
+        };
+        assert_eq!(
+            vec![0.5, 1.0, 1.5],
+            (1..=3).map(|x| backoff.delay(0.5, x)).collect::<Vec<_>>()
+        );
+
+        // Unbounded growth and overflow are capped
+        let backoff = Backoff::exponential(10.0);
+        assert_eq!(MAX_REPEAT_DELAY, backoff.delay(1.0, 10));
+        assert_eq!(MAX_REPEAT_DELAY, backoff.delay(1.0, u32::MAX));
+        assert_eq!(0.0, Backoff::linear(-1.0).delay(1.0, 5));
+    }
+}
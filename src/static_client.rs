@@ -0,0 +1,608 @@
+use crate::{
+    client::{ScrapeClient, BLANK_URL},
+    element::{boxed, Locator, ScrapeElement},
+    pipeline::ScrapeError,
+    value::JsonValue,
+};
+use async_trait::async_trait;
+use ego_tree::{NodeId, NodeRef};
+use hyper::{client::HttpConnector, header::LOCATION, Client, Uri};
+use scraper::{ElementRef, Html, Node, Selector};
+use std::{
+    collections::HashMap,
+    env,
+    fmt::{Debug, Formatter, Result as FormatResult},
+    path::PathBuf,
+    sync::Arc,
+    thread,
+};
+use sxd_document::{dom, Package};
+use sxd_xpath::{nodeset, Context, Factory, Value as XPathValue};
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedSender},
+    oneshot,
+};
+use url::Url;
+
+const MAX_REDIRECTS: usize = 10;
+
+/// Browserless client for server-rendered pages, loads HTML documents from local files and HTTP servers.
+/// Scripts are not executed, and the elements can not be interacted with
+pub struct StaticClient {
+    http: Client<HttpConnector>,
+    fixtures: HashMap<String, Fixture>,
+    offline: bool,
+    document: Option<Arc<StaticDocument>>,
+}
+
+/// Page served instead of the URL contents
+enum Fixture {
+    Html(String),
+    File(PathBuf),
+}
+
+impl StaticClient {
+    pub fn new() -> Self {
+        StaticClient {
+            http: Client::new(),
+            fixtures: HashMap::new(),
+            offline: false,
+            document: None,
+        }
+    }
+
+    /// The page is served from the HTML string whenever the URL is opened
+    pub fn with_page<U: Into<String>, H: Into<String>>(mut self, url: U, html: H) -> Self {
+        self.fixtures
+            .insert(fixture_key(url.into()), Fixture::Html(html.into()));
+        self
+    }
+
+    /// The page is served from the local file whenever the URL is opened
+    pub fn with_fixture<U: Into<String>, P: Into<PathBuf>>(mut self, url: U, path: P) -> Self {
+        self.fixtures
+            .insert(fixture_key(url.into()), Fixture::File(path.into()));
+        self
+    }
+
+    /// Only the fixture pages are served, opening any other URL is a fatal error instead of reaching the files or network
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
+    /// Relative URLs are resolved against the current page, or the working directory if no page is loaded yet
+    fn resolve_url(&self, url: &str) -> Result<Url, ScrapeError> {
+        match (Url::parse(url), &self.document) {
+            (Ok(url), _) => Ok(url),
+            (Err(url::ParseError::RelativeUrlWithoutBase), Some(document)) => {
+                document.url.join(url).map_err(page_load_error)
+            }
+            (Err(url::ParseError::RelativeUrlWithoutBase), None) => env::current_dir()
+                .ok()
+                .and_then(|directory| Url::from_file_path(directory.join(url)).ok())
+                .ok_or_else(|| ScrapeError::PageLoadError(format!("invalid file path {}", url))),
+            (Err(error), _) => Err(page_load_error(error)),
+        }
+    }
+
+    async fn fetch(&self, mut url: Url) -> Result<(Url, Vec<u8>), ScrapeError> {
+        for _ in 0..=MAX_REDIRECTS {
+            match self.fixtures.get(url.as_str()) {
+                Some(Fixture::Html(html)) => return Ok((url, html.as_bytes().to_vec())),
+                Some(Fixture::File(path)) => {
+                    let contents = tokio::fs::read(path).await.map_err(page_load_error)?;
+                    return Ok((url, contents));
+                }
+                None if self.offline => {
+                    return Err(ScrapeError::FixtureError(format!("no fixture for {}", url)));
+                }
+                None => (),
+            }
+
+            match url.scheme() {
+                "file" => {
+                    let path = url
+                        .to_file_path()
+                        .map_err(|_| ScrapeError::PageLoadError(format!("invalid file URL {}", url)))?;
+
+                    let contents = tokio::fs::read(path).await.map_err(page_load_error)?;
+                    return Ok((url, contents));
+                }
+
+                "http" => {
+                    let uri = url.as_str().parse::<Uri>().map_err(page_load_error)?;
+                    let response = self.http.get(uri).await.map_err(page_load_error)?;
+
+                    let status = response.status();
+                    if status.is_redirection() {
+                        let location = response
+                            .headers()
+                            .get(LOCATION)
+                            .and_then(|location| location.to_str().ok())
+                            .ok_or_else(|| ScrapeError::PageLoadError(format!("{} redirect without location", url)))?;
+
+                        url = url.join(location).map_err(page_load_error)?;
+                        continue;
+                    }
+
+                    if !status.is_success() {
+                        return Err(ScrapeError::PageLoadError(format!("{} responded with {}", url, status)));
+                    }
+
+                    let body = hyper::body::to_bytes(response.into_body())
+                        .await
+                        .map_err(page_load_error)?;
+                    return Ok((url, body.to_vec()));
+                }
+
+                scheme => {
+                    return Err(ScrapeError::PageLoadError(format!("unsupported URL scheme {}", scheme)));
+                }
+            }
+        }
+
+        Err(ScrapeError::PageLoadError(format!(
+            "too many redirects loading {}",
+            url
+        )))
+    }
+}
+
+impl Default for StaticClient {
+    fn default() -> Self {
+        StaticClient::new()
+    }
+}
+
+#[async_trait]
+impl ScrapeClient for StaticClient {
+    async fn goto(&mut self, url: &str) -> Result<(), ScrapeError> {
+        let url = self.resolve_url(url)?;
+        let (url, contents) = self.fetch(url).await?;
+        self.document = Some(StaticDocument::new(
+            url,
+            String::from_utf8_lossy(&contents).into_owned(),
+        ));
+        Ok(())
+    }
+
+    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+        match self.document {
+            Some(ref document) => document.find_all(None, search).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn execute(&mut self, _: &str, _: Vec<JsonValue>) -> Result<JsonValue, ScrapeError> {
+        Err(unsupported("script execution"))
+    }
+
+    async fn execute_async(&mut self, _: &str, _: Vec<JsonValue>) -> Result<JsonValue, ScrapeError> {
+        Err(unsupported("script execution"))
+    }
+
+    async fn screenshot(&mut self) -> Result<Vec<u8>, ScrapeError> {
+        Err(unsupported("screenshot"))
+    }
+
+    async fn source(&mut self) -> Result<String, ScrapeError> {
+        match self.document {
+            Some(ref document) => document.run(|document| document.html.root_element().html()).await,
+            None => Ok(String::new()),
+        }
+    }
+
+    /// Blank page URL is returned until a page is opened
+    async fn current_url(&mut self) -> Result<String, ScrapeError> {
+        match self.document {
+            Some(ref document) => Ok(document.url.to_string()),
+            None => Ok(BLANK_URL.to_owned()),
+        }
+    }
+
+    async fn perform_actions(&mut self, _: Vec<JsonValue>) -> Result<(), ScrapeError> {
+        Err(unsupported("input actions"))
+    }
+
+    async fn disconnect(&mut self) -> Result<(), ScrapeError> {
+        self.document = None;
+        Ok(())
+    }
+}
+
+/// Job executed on the document thread with the parsed document
+type DocumentJob = Box<dyn for<'d> FnOnce(&mut ParsedDocument<'d>) + Send>;
+
+/// HTML document shared by the client and the elements found in it. The parsed tree can not be shared between
+/// threads, so the source is parsed once on a dedicated thread, which runs the queries until the document is dropped
+struct StaticDocument {
+    url: Url,
+    jobs: UnboundedSender<DocumentJob>,
+}
+
+impl StaticDocument {
+    fn new(url: Url, source: String) -> Arc<Self> {
+        let (jobs, mut receiver) = unbounded_channel::<DocumentJob>();
+        thread::spawn(move || {
+            let package = Package::new();
+            let mut document = ParsedDocument {
+                html: Html::parse_document(&source),
+                package: &package,
+                xpath: None,
+            };
+
+            while let Some(job) = receiver.blocking_recv() {
+                job(&mut document);
+            }
+        });
+
+        Arc::new(StaticDocument { url, jobs })
+    }
+
+    async fn run<T, F>(&self, job: F) -> Result<T, ScrapeError>
+    where
+        T: Send + 'static,
+        F: for<'d> FnOnce(&mut ParsedDocument<'d>) -> T + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let job: DocumentJob = Box::new(move |document| {
+            let _ = sender.send(job(document));
+        });
+
+        let stopped = || ScrapeError::PageLoadError(format!("{} document thread is stopped", self.url));
+        self.jobs.send(job).map_err(|_| stopped())?;
+        receiver.await.map_err(|_| stopped())
+    }
+
+    /// Descendant elements of the scope node (the whole document if omitted) matching the locator
+    async fn find_all(
+        self: &Arc<Self>,
+        scope: Option<NodeId>,
+        search: Locator<'_>,
+    ) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+        let query = Query::from(search);
+        let nodes = self.run(move |document| document.find_all(scope, &query)).await??;
+
+        let elements = nodes
+            .into_iter()
+            .map(|node| {
+                boxed(StaticElement {
+                    document: self.clone(),
+                    node,
+                })
+            })
+            .collect();
+
+        Ok(elements)
+    }
+}
+
+/// Owned locator passed to the document thread
+enum Query {
+    Css(String),
+    Id(String),
+    LinkText(String),
+    XPath(String),
+}
+
+impl From<Locator<'_>> for Query {
+    fn from(locator: Locator<'_>) -> Self {
+        match locator {
+            Locator::Css(query) => Query::Css(query.to_owned()),
+            Locator::Id(query) => Query::Id(query.to_owned()),
+            Locator::LinkText(query) => Query::LinkText(query.to_owned()),
+            Locator::XPath(query) => Query::XPath(query.to_owned()),
+        }
+    }
+}
+
+/// Parsed tree owned by the document thread, the XPath engine copy is built on the first XPath query
+struct ParsedDocument<'d> {
+    html: Html,
+    package: &'d Package,
+    xpath: Option<XPathDocument<'d>>,
+}
+
+impl<'d> ParsedDocument<'d> {
+    fn element(&self, node: NodeId) -> Result<ElementRef<'_>, ScrapeError> {
+        self.html
+            .tree
+            .get(node)
+            .and_then(ElementRef::wrap)
+            .ok_or(ScrapeError::MissingElement)
+    }
+
+    fn find_all(&mut self, scope: Option<NodeId>, query: &Query) -> Result<Vec<NodeId>, ScrapeError> {
+        let matches: Box<dyn Fn(&ElementRef) -> bool + '_> = match query {
+            Query::Css(query) => {
+                let selector =
+                    Selector::parse(query).map_err(|_| ScrapeError::InvalidQuery(format!("CSS selector {}", query)))?;
+                Box::new(move |element| selector.matches(element))
+            }
+
+            Query::Id(query) => Box::new(move |element| element.value().id() == Some(query.as_str())),
+
+            Query::LinkText(query) => {
+                Box::new(move |element| element.value().name() == "a" && element_text(element) == *query)
+            }
+
+            Query::XPath(query) => {
+                let (package, html) = (self.package, &self.html);
+                let xpath = self.xpath.get_or_insert_with(|| XPathDocument::new(package, html));
+                return xpath.evaluate(scope, query);
+            }
+        };
+
+        let scope = match scope {
+            Some(id) => self.html.tree.get(id).ok_or(ScrapeError::MissingElement)?,
+            None => self.html.tree.root(),
+        };
+
+        Ok(scope
+            .descendants()
+            .skip(1)
+            .filter_map(ElementRef::wrap)
+            .filter(|element| matches(element))
+            .map(|element| element.id())
+            .collect())
+    }
+}
+
+/// Copy of the document built with the XPath engine DOM, mapped to the parsed tree nodes both ways
+// XPath engine DOM nodes are hashed by identity, which is not affected by the node mutations
+#[allow(clippy::mutable_key_type)]
+struct XPathDocument<'d> {
+    document: dom::Document<'d>,
+    elements: HashMap<dom::Element<'d>, NodeId>,
+    nodes: HashMap<NodeId, dom::Element<'d>>,
+}
+
+#[allow(clippy::mutable_key_type)]
+impl<'d> XPathDocument<'d> {
+    fn new(package: &'d Package, html: &Html) -> Self {
+        let document = package.as_document();
+        let mut elements = HashMap::new();
+        for child in html.tree.root().children() {
+            copy_node(
+                &document,
+                dom::ParentOfChild::Root(document.root()),
+                child,
+                &mut elements,
+            );
+        }
+
+        let nodes = elements.iter().map(|(element, node)| (*node, *element)).collect();
+        XPathDocument {
+            document,
+            elements,
+            nodes,
+        }
+    }
+
+    fn evaluate(&self, scope: Option<NodeId>, query: &str) -> Result<Vec<NodeId>, ScrapeError> {
+        let error = || ScrapeError::InvalidQuery(format!("XPath {}", query));
+        let xpath = Factory::new().build(query).ok().flatten().ok_or_else(error)?;
+
+        let context = match scope.and_then(|node| self.nodes.get(&node)) {
+            Some(element) => nodeset::Node::Element(*element),
+            None => nodeset::Node::Root(self.document.root()),
+        };
+
+        match xpath.evaluate(&Context::new(), context).map_err(|_| error())? {
+            XPathValue::Nodeset(nodeset) => Ok(nodeset
+                .document_order()
+                .into_iter()
+                .filter_map(|node| match node {
+                    nodeset::Node::Element(element) => self.elements.get(&element).copied(),
+                    _ => None,
+                })
+                .collect()),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// Element of the static HTML document
+#[derive(Clone)]
+pub struct StaticElement {
+    document: Arc<StaticDocument>,
+    node: NodeId,
+}
+
+impl Debug for StaticElement {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "StaticElement({:?} in {})", self.node, self.document.url)
+    }
+}
+
+#[async_trait]
+impl ScrapeElement for StaticElement {
+    async fn text(&mut self) -> Result<String, ScrapeError> {
+        let node = self.node;
+        self.document
+            .run(move |document| document.element(node).map(|element| element_text(&element)))
+            .await?
+    }
+
+    async fn attr(&mut self, name: &str) -> Result<Option<String>, ScrapeError> {
+        let (node, name) = (self.node, name.to_owned());
+        self.document
+            .run(move |document| {
+                document
+                    .element(node)
+                    .map(|element| element.value().attr(&name).map(str::to_owned))
+            })
+            .await?
+    }
+
+    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+        self.document.find_all(Some(self.node), search).await
+    }
+
+    async fn click(&mut self) -> Result<(), ScrapeError> {
+        Err(unsupported("element click"))
+    }
+
+    async fn send_keys(&mut self, _: &str) -> Result<(), ScrapeError> {
+        Err(unsupported("element input"))
+    }
+
+    async fn screenshot(&mut self) -> Result<Vec<u8>, ScrapeError> {
+        Err(unsupported("screenshot"))
+    }
+
+    fn reference(&self) -> Option<JsonValue> {
+        None
+    }
+
+    fn clone_element(&self) -> Box<dyn ScrapeElement> {
+        Box::new(self.clone())
+    }
+}
+
+/// Text content with the whitespace collapsed, similar to the text rendered by a browser
+fn element_text(element: &ElementRef) -> String {
+    element
+        .text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[allow(clippy::mutable_key_type)]
+fn copy_node<'d>(
+    document: &dom::Document<'d>,
+    parent: dom::ParentOfChild<'d>,
+    node: NodeRef<Node>,
+    nodes: &mut HashMap<dom::Element<'d>, NodeId>,
+) {
+    match node.value() {
+        Node::Element(value) => {
+            let element = document.create_element(value.name());
+            for (name, value) in value.attrs() {
+                element.set_attribute_value(name, value);
+            }
+
+            append_child(parent, element);
+            nodes.insert(element, node.id());
+            for child in node.children() {
+                copy_node(document, dom::ParentOfChild::Element(element), child, nodes);
+            }
+        }
+
+        Node::Text(text) => {
+            if let dom::ParentOfChild::Element(element) = parent {
+                element.append_child(document.create_text(text));
+            }
+        }
+
+        _ => (),
+    }
+}
+
+fn append_child<'d>(parent: dom::ParentOfChild<'d>, element: dom::Element<'d>) {
+    match parent {
+        dom::ParentOfChild::Root(root) => root.append_child(element),
+        dom::ParentOfChild::Element(parent) => parent.append_child(element),
+    }
+}
+
+/// Fixture URLs are normalized the same way as the opened URLs
+fn fixture_key(url: String) -> String {
+    Url::parse(&url).map(String::from).unwrap_or(url)
+}
+
+fn page_load_error<E: ToString>(error: E) -> ScrapeError {
+    ScrapeError::PageLoadError(error.to_string())
+}
+
+fn unsupported(operation: &str) -> ScrapeError {
+    ScrapeError::UnsupportedOperation(format!("{} is not supported by the static HTML client", operation))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{OpenUrl, QueryElement, Selector, SetModelAttribute, StoreModel},
+        client::ScrapeClient,
+        element::Locator,
+        pipeline::{ScrapeContext, ScrapePipeline},
+        static_client::StaticClient,
+        value::Value,
+    };
+    use serde_json::json;
+    use std::{env, fs, path::PathBuf};
+
+    const PAGE: &str = r#"
+        <html>
+            <body>
+                <div id="cards">
+                    <article class="card"><h2>First   card</h2><span data-price="100">100 ₽</span></article>
+                    <article class="card"><h2>Second card</h2><span data-price="200">200 ₽</span></article>
+                </div>
+                <a href="?page=2">Next page</a>
+            </body>
+        </html>
+    "#;
+
+    fn fixture(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(name);
+        fs::write(&path, PAGE).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_find_elements() {
+        let path = fixture("scrapman-static-find.html");
+        let mut client = StaticClient::new();
+        client.goto(path.to_str().unwrap()).await.unwrap();
+
+        let mut cards = client.find_all(Locator::Css("article.card")).await.unwrap();
+        assert_eq!(2, cards.len());
+        assert_eq!("First card 100 ₽", cards[0].text().await.unwrap());
+
+        let mut prices = cards[1].find_all(Locator::XPath(".//span")).await.unwrap();
+        assert_eq!(Some("200".to_owned()), prices[0].attr("data-price").await.unwrap());
+
+        assert_eq!(1, client.find_all(Locator::Id("cards")).await.unwrap().len());
+        assert_eq!(1, client.find_all(Locator::LinkText("Next page")).await.unwrap().len());
+        assert_eq!(2, client.find_all(Locator::XPath("//h2")).await.unwrap().len());
+        assert_eq!(true, client.find_all(Locator::Css("div[")).await.is_err());
+
+        // Elements keep the document they were found in after the next page is opened
+        client.goto(path.to_str().unwrap()).await.unwrap();
+        assert_eq!("Second card 200 ₽", cards[1].text().await.unwrap());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_static_pipeline() {
+        let path = fixture("scrapman-static-pipeline.html");
+        let pipeline = ScrapePipeline::default()
+            .push(OpenUrl::new(Value::constant(path.to_str().unwrap())))
+            .push(
+                QueryElement::global(Selector::Css, Value::constant("article")).for_each(
+                    ScrapePipeline::default()
+                        .push(QueryElement::scoped(Selector::TagName, Value::constant("h2")))
+                        .push(SetModelAttribute::new("title", Value::ElementText))
+                        .push(QueryElement::scoped(Selector::Css, Value::constant("span")))
+                        .push(SetModelAttribute::new("price", Value::element_attribute("data-price")))
+                        .push(StoreModel),
+                ),
+            );
+
+        let mut context = ScrapeContext::new(StaticClient::new(), None);
+        pipeline.execute(&mut context).await.unwrap();
+        assert_eq!(
+            vec![
+                json!({"title": "First card", "price": "100"}),
+                json!({"title": "Second card", "price": "200"})
+            ],
+            context.models
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+}
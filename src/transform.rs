@@ -0,0 +1,222 @@
+use crate::{
+    pipeline::ScrapeError,
+    value::{to_string, JsonValue},
+};
+use regex::{Error as RegexError, Regex};
+use serde::{de::Error as DeserializeError, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Number;
+use std::{
+    fmt::{Display, Formatter, Result as FormatResult},
+    ops::Deref,
+};
+
+/// Regex pattern compiled once the transformation or condition is created or deserialized, so invalid patterns are
+/// reported when the pipeline is loaded
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, RegexError> {
+        Regex::new(pattern).map(Pattern)
+    }
+}
+
+impl Deref for Pattern {
+    type Target = Regex;
+
+    fn deref(&self) -> &Regex {
+        &self.0
+    }
+}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Pattern::new(&pattern).map_err(D::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Transform {
+    Trim,
+    CollapseWhitespace,
+    RegexCapture {
+        pattern: Pattern,
+        #[serde(default = "default_capture_group")]
+        group: usize,
+    },
+    Replace {
+        pattern: Pattern,
+        replacement: String,
+    },
+    Uppercase,
+    Lowercase,
+    StripNonDigits,
+    ParseInt,
+    ParseFloat,
+}
+
+fn default_capture_group() -> usize {
+    1
+}
+
+impl Display for Transform {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+impl Transform {
+    /// Fails with the `ValueTransformError` error kind if the pattern is not a valid regex
+    pub fn regex_capture(pattern: &str, group: usize) -> Result<Self, ScrapeError> {
+        Ok(Transform::RegexCapture {
+            pattern: compile(pattern)?,
+            group,
+        })
+    }
+
+    /// Regex based replacement, capture groups can be referenced in the replacement string as `$1`
+    pub fn replace<R: Into<String>>(pattern: &str, replacement: R) -> Result<Self, ScrapeError> {
+        Ok(Transform::Replace {
+            pattern: compile(pattern)?,
+            replacement: replacement.into(),
+        })
+    }
+
+    /// Applies the transformation to the value, non-string values are transformed in the serialized form
+    pub fn apply(&self, value: JsonValue) -> Result<JsonValue, ScrapeError> {
+        let value = match (self, value) {
+            (Transform::ParseInt, value @ JsonValue::Number(_)) if value.is_i64() => return Ok(value),
+            (Transform::ParseFloat, value @ JsonValue::Number(_)) => return Ok(value),
+            (_, value) => to_string(value).unwrap_or_default(),
+        };
+
+        match self {
+            Transform::Trim => Ok(JsonValue::String(value.trim().to_owned())),
+
+            Transform::CollapseWhitespace => Ok(JsonValue::String(
+                value.split_whitespace().collect::<Vec<_>>().join(" "),
+            )),
+
+            // Missing match or capture group results in an empty value
+            Transform::RegexCapture { pattern, group } => Ok(pattern
+                .captures(&value)
+                .and_then(|captures| captures.get(*group))
+                .map(|capture| JsonValue::String(capture.as_str().to_owned()))
+                .unwrap_or_default()),
+
+            Transform::Replace { pattern, replacement } => Ok(JsonValue::String(
+                pattern.replace_all(&value, replacement.as_str()).into_owned(),
+            )),
+
+            Transform::Uppercase => Ok(JsonValue::String(value.to_uppercase())),
+
+            Transform::Lowercase => Ok(JsonValue::String(value.to_lowercase())),
+
+            Transform::StripNonDigits => Ok(JsonValue::String(value.chars().filter(char::is_ascii_digit).collect())),
+
+            // Whitespace digit group separators are ignored: "12 500 000" is parsed as 12500000
+            Transform::ParseInt => remove_whitespace(&value)
+                .parse::<i64>()
+                .map(JsonValue::from)
+                .map_err(|_| ScrapeError::ValueTransformError),
+
+            // Comma is accepted as a decimal separator if there is no decimal point in the value
+            Transform::ParseFloat => {
+                let mut value = remove_whitespace(&value);
+                if !value.contains('.') {
+                    value = value.replace(',', ".");
+                }
+
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+                    .map(JsonValue::Number)
+                    .ok_or(ScrapeError::ValueTransformError)
+            }
+        }
+    }
+}
+
+fn compile(pattern: &str) -> Result<Pattern, ScrapeError> {
+    Pattern::new(pattern).map_err(|_| ScrapeError::ValueTransformError)
+}
+
+fn remove_whitespace(value: &str) -> String {
+    value.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::Transform;
+    use crate::{
+        client::MockScrapeClient,
+        pipeline::ScrapeContext,
+        value::{JsonValue, Value},
+    };
+    use serde_json::json;
+
+    fn apply(transform: Transform, value: &str) -> JsonValue {
+        transform.apply(json!(value)).unwrap()
+    }
+
+    #[test]
+    fn test_whitespace() {
+        assert_eq!(json!("a  b"), apply(Transform::Trim, "  a  b\n"));
+        assert_eq!(json!("a b"), apply(Transform::CollapseWhitespace, "  a \n\t b "));
+    }
+
+    #[test]
+    fn test_regex() {
+        let transform = Transform::regex_capture(r"(\d+) комн", 1).unwrap();
+        assert_eq!(json!("2"), apply(transform.clone(), "2 комн. квартира"));
+        assert_eq!(JsonValue::Null, apply(transform, "студия"));
+
+        let transform = Transform::replace(r"(\w+)@(\w+)", "$2 at $1").unwrap();
+        assert_eq!(json!("host at user"), apply(transform, "user@host"));
+
+        assert_eq!(true, Transform::regex_capture("(", 1).is_err());
+        assert_eq!(
+            true,
+            serde_yaml::from_str::<Transform>("RegexCapture: { pattern: \"(\" }").is_err()
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(json!("12500000"), apply(Transform::StripNonDigits, "12 500 000 ₽"));
+        assert_eq!(json!(12500000), apply(Transform::ParseInt, "12\u{a0}500\u{a0}000"));
+        assert_eq!(json!(45.5), apply(Transform::ParseFloat, "45,5"));
+        assert_eq!(json!(1234.5), apply(Transform::ParseFloat, "1 234.5"));
+        assert_eq!(json!(7), Transform::ParseInt.apply(json!(7)).unwrap());
+        assert_eq!(true, Transform::ParseInt.apply(json!("12 500 ₽")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_yaml_transform_chain() {
+        let value: Value = serde_yaml::from_str(
+            r#"
+            Transformed:
+              value:
+                Constant: " Цена: 12 500 000 ₽ "
+              transforms:
+                - Trim
+                - RegexCapture:
+                    pattern: "Цена: (.+)"
+                - StripNonDigits
+                - ParseInt
+            "#,
+        )
+        .unwrap();
+
+        let mut context = ScrapeContext::new(MockScrapeClient::new(), None);
+        assert_eq!(json!(12500000), value.resolve(&mut context).await.unwrap());
+    }
+}
@@ -0,0 +1,341 @@
+use crate::{
+    pipeline::ScrapePipeline,
+    stage::{is_valid_delay, FlowControl, ScrapeStage},
+};
+use std::{
+    collections::HashSet,
+    error::Error,
+    fmt::{Display, Formatter, Result as FormatResult},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    UnknownGotoTarget { stage: String, target: String },
+    DuplicateStageName { stage: String },
+    UnreachableStage { stage: String },
+    InfiniteRepeat { stage: String },
+    InvalidDelay { stage: String },
+}
+
+impl ValidationError {
+    /// Unreachable stages do not affect the pipeline execution, and are reported as warnings only
+    pub fn is_warning(&self) -> bool {
+        matches!(self, ValidationError::UnreachableStage { .. })
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            ValidationError::UnknownGotoTarget { stage, target } => {
+                write!(fmt, "{} redirects to unknown stage \"{}\"", stage, target)
+            }
+
+            ValidationError::DuplicateStageName { stage } => {
+                write!(fmt, "{} name is not unique within the pipeline", stage)
+            }
+
+            ValidationError::UnreachableStage { stage } => {
+                write!(fmt, "{} is never executed", stage)
+            }
+
+            ValidationError::InfiniteRepeat { stage } => {
+                write!(
+                    fmt,
+                    "{} is repeated without delay on every outcome, and never completes",
+                    stage
+                )
+            }
+
+            ValidationError::InvalidDelay { stage } => {
+                write!(fmt, "{} delay is negative or not a finite number of seconds", stage)
+            }
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+impl ScrapePipeline {
+    /// Static pipeline check, nested pipelines of the stage actions are validated recursively
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        validate_pipeline(self, "", &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+pub(crate) fn stage_path(idx: usize, name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("stages[{}] \"{}\"", idx, name),
+        None => format!("stages[{}]", idx),
+    }
+}
+
+fn validate_pipeline(pipeline: &ScrapePipeline, prefix: &str, errors: &mut Vec<ValidationError>) {
+    let stages = pipeline.stages();
+    let path = |idx: usize| format!("{}{}", prefix, stage_path(idx, stages[idx].name.as_deref()));
+
+    let mut names = HashSet::new();
+    for (idx, stage) in stages.iter().enumerate() {
+        if let Some(ref name) = stage.name {
+            if !names.insert(name) {
+                errors.push(ValidationError::DuplicateStageName { stage: path(idx) });
+            }
+        }
+
+        let delays = stage.action.delays();
+        if !flows(stage).all(FlowControl::has_valid_delays) || !delays.into_iter().all(is_valid_delay) {
+            errors.push(ValidationError::InvalidDelay { stage: path(idx) });
+        }
+
+        for flow in flows(stage) {
+            if let FlowControl::Goto(target) = flow {
+                if pipeline.stage_position(target).is_none() {
+                    errors.push(ValidationError::UnknownGotoTarget {
+                        stage: path(idx),
+                        target: target.clone(),
+                    });
+                }
+            }
+        }
+
+        // Repeats with a delay are polling loops, e.g. waiting for the page to update, and are allowed
+        let tight_repeat = |flow: &FlowControl| {
+            matches!(
+                flow,
+                FlowControl::Repeat {
+                    delay: None,
+                    max_attempts: None,
+                    ..
+                }
+            )
+        };
+        if flows(stage).all(tight_repeat) {
+            errors.push(ValidationError::InfiniteRepeat { stage: path(idx) });
+        }
+    }
+
+    // Every flow control branch is considered possible, stages are traversed starting from the first one
+    let mut reachable = vec![false; stages.len()];
+    let mut queue = vec![0];
+    while let Some(idx) = queue.pop() {
+        if idx >= stages.len() || reachable[idx] {
+            continue;
+        }
+
+        reachable[idx] = true;
+        for flow in flows(&stages[idx]) {
+            match flow {
+                FlowControl::Continue => queue.push(idx + 1),
+                FlowControl::Goto(target) => queue.extend(pipeline.stage_position(target)),
+                FlowControl::Quit | FlowControl::Repeat { .. } => (),
+            }
+        }
+    }
+
+    for (idx, stage) in stages.iter().enumerate() {
+        if !reachable[idx] {
+            errors.push(ValidationError::UnreachableStage { stage: path(idx) });
+        }
+
+        if let Some((field, nested)) = stage.action.nested_pipeline() {
+            validate_pipeline(nested, &format!("{} > {}.", path(idx), field), errors);
+        }
+    }
+}
+
+/// Bounded repeat without the fallback flow continues the pipeline once the attempts are exhausted
+static IMPLICIT_FALLBACK: FlowControl = FlowControl::Continue;
+
+/// All flow control branches of the stage, including the flows chosen by the action and the repeat fallbacks
+fn flows(stage: &ScrapeStage) -> impl Iterator<Item = &FlowControl> {
+    let mut flows = vec![&stage.on_complete, &stage.on_error];
+    flows.extend(stage.on_error_kind.values());
+    flows.extend(stage.action.flows());
+
+    let mut idx = 0;
+    while idx < flows.len() {
+        match flows[idx] {
+            FlowControl::Repeat {
+                fallback: Some(fallback),
+                ..
+            } => flows.push(fallback),
+            FlowControl::Repeat {
+                max_attempts: Some(_),
+                fallback: None,
+                ..
+            } => flows.push(&IMPLICIT_FALLBACK),
+            _ => (),
+        }
+        idx += 1;
+    }
+
+    flows.into_iter()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        action::{QueryElement, ScrollUntilLoaded, Selector, TestError, TestSuccess},
+        pipeline::{ScrapeErrorKind, ScrapePipeline},
+        stage::{Backoff, FlowControl, ScrapeStage},
+        validation::ValidationError,
+        value::Value,
+    };
+
+    #[test]
+    fn test_valid_pipeline() {
+        let pipeline = ScrapePipeline::default()
+            .push(ScrapeStage::from(TestSuccess).with_name("Start"))
+            .push(ScrapeStage::from(TestError).on_any_error(FlowControl::goto("Start")))
+            .push(ScrapeStage::from(TestSuccess).on_complete(FlowControl::repeat_with_delay(1.0)))
+            .push(
+                ScrapeStage::from(TestSuccess)
+                    .on_complete(FlowControl::repeat_with_delay(5.0))
+                    .on_any_error(FlowControl::repeat_with_delay(5.0)),
+            );
+
+        assert_eq!(Ok(()), pipeline.validate());
+    }
+
+    #[test]
+    fn test_bounded_repeat() {
+        let pipeline = ScrapePipeline::default()
+            .push(
+                ScrapeStage::from(TestSuccess)
+                    .on_complete(FlowControl::repeat().with_max_attempts(5, FlowControl::goto("Finish")))
+                    .on_any_error(FlowControl::repeat()),
+            )
+            .push(TestSuccess)
+            .push(ScrapeStage::from(TestSuccess).with_name("Finish"));
+
+        let errors = pipeline.validate().unwrap_err();
+        assert_eq!(
+            vec![ValidationError::UnreachableStage {
+                stage: "stages[1]".into()
+            }],
+            errors
+        );
+    }
+
+    #[test]
+    fn test_bounded_repeat_without_fallback() {
+        let bounded = FlowControl::Repeat {
+            delay: None,
+            backoff: None,
+            max_attempts: Some(3),
+            fallback: None,
+        };
+        let pipeline = ScrapePipeline::default()
+            .push(
+                ScrapeStage::from(TestSuccess)
+                    .on_complete(bounded.clone())
+                    .on_any_error(bounded),
+            )
+            .push(TestSuccess);
+
+        assert_eq!(Ok(()), pipeline.validate());
+    }
+
+    #[test]
+    fn test_invalid_delay() {
+        let pipeline = ScrapePipeline::default()
+            .push(ScrapeStage::from(TestSuccess).on_any_error(FlowControl::repeat_with_delay(-1.0)))
+            .push(
+                ScrapeStage::from(TestSuccess)
+                    .on_any_error(FlowControl::repeat_with_delay(1.0).with_backoff(Backoff::exponential(f64::NAN))),
+            )
+            .push(
+                ScrapeStage::from(TestSuccess)
+                    .on_any_error(FlowControl::repeat_with_delay(1.0).with_backoff(Backoff::linear(0.5))),
+            )
+            .push(ScrollUntilLoaded::global(Selector::Css, Value::constant("article")).with_delay(f64::NAN));
+
+        let errors = pipeline.validate().unwrap_err();
+        assert_eq!(
+            vec![
+                ValidationError::InvalidDelay {
+                    stage: "stages[0]".into()
+                },
+                ValidationError::InvalidDelay {
+                    stage: "stages[1]".into()
+                },
+                ValidationError::InvalidDelay {
+                    stage: "stages[3]".into()
+                },
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn test_invalid_pipeline() {
+        let pipeline = ScrapePipeline::default()
+            .push(
+                ScrapeStage::from(TestSuccess)
+                    .with_name("Start")
+                    .on_complete(FlowControl::goto("Finish"))
+                    .on_any_error(FlowControl::Quit)
+                    .on_error_kind(ScrapeErrorKind::TestError, FlowControl::goto("Missing")),
+            )
+            .push(ScrapeStage::from(TestSuccess).with_name("Start"))
+            .push(
+                ScrapeStage::from(TestSuccess)
+                    .with_name("Finish")
+                    .on_complete(FlowControl::repeat())
+                    .on_any_error(FlowControl::repeat()),
+            );
+
+        let errors = pipeline.validate().unwrap_err();
+        assert_eq!(
+            vec![
+                ValidationError::UnknownGotoTarget {
+                    stage: "stages[0] \"Start\"".into(),
+                    target: "Missing".into()
+                },
+                ValidationError::DuplicateStageName {
+                    stage: "stages[1] \"Start\"".into()
+                },
+                ValidationError::InfiniteRepeat {
+                    stage: "stages[2] \"Finish\"".into()
+                },
+                ValidationError::UnreachableStage {
+                    stage: "stages[1] \"Start\"".into()
+                },
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn test_nested_pipeline() {
+        let pipeline = ScrapePipeline::default().push(
+            QueryElement::global(Selector::Css, Value::constant("article")).for_each(
+                ScrapePipeline::default()
+                    .push(
+                        ScrapeStage::from(TestSuccess)
+                            .on_complete(FlowControl::goto("Outer"))
+                            .on_any_error(FlowControl::Quit),
+                    )
+                    .push(TestSuccess),
+            ),
+        );
+
+        let errors = pipeline.validate().unwrap_err();
+        assert_eq!(2, errors.len());
+        assert_eq!(
+            ValidationError::UnknownGotoTarget {
+                stage: "stages[0] > for_each.stages[0]".into(),
+                target: "Outer".into()
+            },
+            errors[0]
+        );
+        assert_eq!(true, errors[1].is_warning());
+    }
+}
@@ -1,16 +1,100 @@
-use crate::pipeline::{ScrapeContext, ScrapeError};
+use crate::{
+    pipeline::{ScrapeContext, ScrapeError},
+    transform::Transform,
+};
+use futures::future::{BoxFuture, FutureExt};
 use json_dotpath::DotPaths;
 use serde::{Deserialize, Serialize};
+use serde_json::Number;
 use std::fmt::{Display, Formatter, Result as FormatResult};
 
 pub type JsonValue = serde_json::Value;
 
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum ValueType {
+    String,
+    Number,
+    Bool,
+    Array,
+    Object,
+    Null,
+}
+
+impl ValueType {
+    /// Converts the resolved value to the target type, missing (null) value is never converted
+    pub fn convert(&self, value: JsonValue) -> Result<JsonValue, ScrapeError> {
+        let error = || ScrapeError::ValueConversionError(*self);
+        match (self, value) {
+            (_, JsonValue::Null) => Ok(JsonValue::Null),
+
+            (ValueType::String, value) => Ok(JsonValue::String(to_string(value).unwrap_or_default())),
+
+            (ValueType::Number, value @ JsonValue::Number(_)) => Ok(value),
+            (ValueType::Number, JsonValue::String(value)) => {
+                let value = value.trim();
+                match value.parse::<i64>() {
+                    Ok(number) => Ok(JsonValue::from(number)),
+                    Err(_) => value
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(Number::from_f64)
+                        .map(JsonValue::Number)
+                        .ok_or_else(error),
+                }
+            }
+
+            (ValueType::Bool, value @ JsonValue::Bool(_)) => Ok(value),
+            (ValueType::Bool, JsonValue::String(value)) => match value.trim().to_lowercase().as_str() {
+                "true" | "1" => Ok(JsonValue::Bool(true)),
+                "false" | "0" => Ok(JsonValue::Bool(false)),
+                _ => Err(error()),
+            },
+            (ValueType::Bool, JsonValue::Number(number)) => match number.as_f64() {
+                Some(number) => Ok(JsonValue::Bool(number != 0.0)),
+                None => Err(error()),
+            },
+
+            // Strings are parsed as JSON documents when converted to composite types
+            (ValueType::Array, value @ JsonValue::Array(_)) => Ok(value),
+            (ValueType::Array, JsonValue::String(value)) => match serde_json::from_str(&value) {
+                Ok(value @ JsonValue::Array(_)) => Ok(value),
+                _ => Err(error()),
+            },
+
+            (ValueType::Object, value @ JsonValue::Object(_)) => Ok(value),
+            (ValueType::Object, JsonValue::String(value)) => match serde_json::from_str(&value) {
+                Ok(value @ JsonValue::Object(_)) => Ok(value),
+                _ => Err(error()),
+            },
+
+            // Blank strings are treated as missing values
+            (ValueType::Null, JsonValue::String(value)) if value.trim().is_empty() => Ok(JsonValue::Null),
+
+            _ => Err(error()),
+        }
+    }
+}
+
+impl Display for ValueType {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> FormatResult {
+        write!(fmt, "{:?}", self)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Value {
-    Constant(String),
+    Constant(JsonValue),
     Context(String),
     ElementText,
     ElementAttribute(String),
+    Transformed {
+        value: Box<Value>,
+        transforms: Vec<Transform>,
+    },
+    Typed {
+        value: Box<Value>,
+        value_type: ValueType,
+    },
 }
 
 impl Display for Value {
@@ -20,7 +104,7 @@ impl Display for Value {
 }
 
 impl Value {
-    pub fn constant<T: Into<String>>(value: T) -> Self {
+    pub fn constant<T: Into<JsonValue>>(value: T) -> Self {
         Value::Constant(value.into())
     }
 
@@ -32,39 +116,155 @@ impl Value {
         Value::ElementAttribute(attribute.into())
     }
 
-    pub async fn resolve(&self, context: &mut ScrapeContext) -> Result<Option<String>, ScrapeError> {
+    /// Appends a transformation to the value resolution chain
+    pub fn transform(self, transform: Transform) -> Self {
         match self {
-            Value::Constant(value) => Ok(Some(value.to_owned())),
-
-            Value::Context(key) => context
-                .values
-                .dot_get::<JsonValue>(&key)
-                .map(to_string)
-                .map_err(|_| ScrapeError::ValueResolveError),
-
-            Value::ElementText => {
-                let element = context.current_element.as_mut().ok_or(ScrapeError::MissingElement)?;
-                element
-                    .text()
-                    .await
-                    .map(Option::Some)
-                    .map_err(ScrapeError::WebdriverCommandError)
+            Value::Transformed { value, mut transforms } => {
+                transforms.push(transform);
+                Value::Transformed { value, transforms }
             }
 
-            Value::ElementAttribute(attribute) => {
-                let element = context.current_element.as_mut().ok_or(ScrapeError::MissingElement)?;
-                element
-                    .attr(attribute)
-                    .await
-                    .map_err(ScrapeError::WebdriverCommandError)
+            value => Value::Transformed {
+                value: Box::new(value),
+                transforms: vec![transform],
+            },
+        }
+    }
+
+    /// Declares the target type the resolved value is converted to
+    pub fn typed(self, value_type: ValueType) -> Self {
+        Value::Typed {
+            value: Box::new(self),
+            value_type,
+        }
+    }
+
+    /// Resolves the value, missing value is represented as JSON null
+    pub fn resolve<'a>(&'a self, context: &'a mut ScrapeContext) -> BoxFuture<'a, Result<JsonValue, ScrapeError>> {
+        async move {
+            match self {
+                Value::Constant(value) => Ok(value.clone()),
+
+                Value::Context(key) => context
+                    .values
+                    .dot_get::<JsonValue>(&key)
+                    .map(Option::unwrap_or_default)
+                    .map_err(|_| ScrapeError::ValueResolveError),
+
+                Value::ElementText => {
+                    let element = context.current_element.as_mut().ok_or(ScrapeError::MissingElement)?;
+                    element.text().await.map(JsonValue::String)
+                }
+
+                Value::ElementAttribute(attribute) => {
+                    let element = context.current_element.as_mut().ok_or(ScrapeError::MissingElement)?;
+                    element
+                        .attr(attribute)
+                        .await
+                        .map(|value| value.map(JsonValue::String).unwrap_or_default())
+                }
+
+                // Transformations are applied sequentially, missing value is passed through the chain as is
+                Value::Transformed { value, transforms } => {
+                    let mut value = value.resolve(context).await?;
+                    for transform in transforms {
+                        if value.is_null() {
+                            break;
+                        }
+
+                        value = transform.apply(value)?;
+                    }
+
+                    Ok(value)
+                }
+
+                Value::Typed { value, value_type } => value_type.convert(value.resolve(context).await?),
             }
         }
+        .boxed()
+    }
+
+    /// Resolves the value as a string, non-string values are serialized to JSON
+    pub async fn resolve_string(&self, context: &mut ScrapeContext) -> Result<Option<String>, ScrapeError> {
+        self.resolve(context).await.map(to_string)
     }
 }
 
-fn to_string(value: Option<JsonValue>) -> Option<String> {
-    value.map(|value| match value {
-        JsonValue::String(value) => value.clone(),
-        value => value.to_string(),
-    })
+pub(crate) fn to_string(value: JsonValue) -> Option<String> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::String(value) => Some(value),
+        value => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        client::MockScrapeClient,
+        element::MockScrapeElement,
+        pipeline::{ScrapeContext, ScrapeError},
+        transform::Transform,
+        value::{JsonValue, Value, ValueType},
+    };
+    use futures::future;
+    use mockall::predicate;
+    use serde_json::json;
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(json!(12500000), ValueType::Number.convert(json!(" 12500000 ")).unwrap());
+        assert_eq!(json!(45.5), ValueType::Number.convert(json!("45.5")).unwrap());
+        assert_eq!(json!(true), ValueType::Bool.convert(json!("True")).unwrap());
+        assert_eq!(json!("5"), ValueType::String.convert(json!(5)).unwrap());
+        assert_eq!(json!([1, 2]), ValueType::Array.convert(json!("[1, 2]")).unwrap());
+        assert_eq!(
+            json!({"a": 1}),
+            ValueType::Object.convert(json!(r#"{"a": 1}"#)).unwrap()
+        );
+        assert_eq!(JsonValue::Null, ValueType::Null.convert(json!(" ")).unwrap());
+        assert_eq!(JsonValue::Null, ValueType::Number.convert(JsonValue::Null).unwrap());
+    }
+
+    #[test]
+    fn test_convert_error() {
+        match ValueType::Number.convert(json!("12 500 ₽")) {
+            Err(ScrapeError::ValueConversionError(ValueType::Number)) => (),
+            result => panic!("unexpected conversion result: {:?}", result),
+        }
+
+        assert_eq!(true, ValueType::Array.convert(json!(r#"{"a": 1}"#)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_element_value() {
+        let mut element = MockScrapeElement::new();
+        element
+            .expect_text()
+            .returning(|| Box::pin(future::ok(" 12 500 ₽ ".to_owned())));
+        element
+            .expect_attr()
+            .with(predicate::eq("href"))
+            .returning(|_| Box::pin(future::ok(Some("/offer/1".to_owned()))));
+        element
+            .expect_attr()
+            .with(predicate::eq("title"))
+            .returning(|_| Box::pin(future::ok(None)));
+
+        let mut context = ScrapeContext::new(MockScrapeClient::new(), None);
+        context.current_element = Some(Box::new(element));
+
+        let price = Value::ElementText
+            .transform(Transform::StripNonDigits)
+            .typed(ValueType::Number);
+        assert_eq!(json!(12500), price.resolve(&mut context).await.unwrap());
+        assert_eq!(
+            json!("/offer/1"),
+            Value::element_attribute("href").resolve(&mut context).await.unwrap()
+        );
+        assert_eq!(
+            JsonValue::Null,
+            Value::element_attribute("title").resolve(&mut context).await.unwrap()
+        );
+    }
 }
@@ -0,0 +1,236 @@
+use crate::{
+    client::ScrapeClient,
+    element::{boxed, Locator, ScrapeElement},
+    pipeline::ScrapeError,
+    value::JsonValue,
+};
+use async_trait::async_trait;
+use fantoccini::{elements::Element, error::CmdError, Client};
+use hyper::{client::HttpConnector, header::CONTENT_TYPE, Body, Request};
+use hyper_tls::HttpsConnector;
+use serde_json::json;
+use webdriver::error::{ErrorStatus, WebDriverError};
+
+impl<'a> From<Locator<'a>> for fantoccini::Locator<'a> {
+    fn from(locator: Locator<'a>) -> Self {
+        match locator {
+            Locator::Css(query) => fantoccini::Locator::Css(query),
+            Locator::Id(query) => fantoccini::Locator::Id(query),
+            Locator::LinkText(query) => fantoccini::Locator::LinkText(query),
+            Locator::XPath(query) => fantoccini::Locator::XPath(query),
+        }
+    }
+}
+
+#[async_trait]
+impl ScrapeClient for Client {
+    async fn goto(&mut self, url: &str) -> Result<(), ScrapeError> {
+        self.goto(url).await.map_err(ScrapeError::WebdriverCommandError)
+    }
+
+    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+        self.find_all(search.into())
+            .await
+            .map(|elements| elements.into_iter().map(boxed).collect())
+            .map_err(ScrapeError::WebdriverCommandError)
+    }
+
+    async fn execute(&mut self, script: &str, args: Vec<JsonValue>) -> Result<JsonValue, ScrapeError> {
+        self.execute(script, args).await.map_err(script_error)
+    }
+
+    async fn execute_async(&mut self, script: &str, args: Vec<JsonValue>) -> Result<JsonValue, ScrapeError> {
+        self.execute_async(script, args).await.map_err(script_error)
+    }
+
+    async fn screenshot(&mut self) -> Result<Vec<u8>, ScrapeError> {
+        self.screenshot().await.map_err(ScrapeError::WebdriverCommandError)
+    }
+
+    async fn source(&mut self) -> Result<String, ScrapeError> {
+        self.source().await.map_err(ScrapeError::WebdriverCommandError)
+    }
+
+    async fn current_url(&mut self) -> Result<String, ScrapeError> {
+        self.current_url()
+            .await
+            .map(String::from)
+            .map_err(ScrapeError::WebdriverCommandError)
+    }
+
+    /// The WebDriver server URL is unknown to the client, `WebDriverClient` performs the actions instead. Mouse
+    /// actions dispatch the script events with this client
+    async fn perform_actions(&mut self, _: Vec<JsonValue>) -> Result<(), ScrapeError> {
+        Err(ScrapeError::UnsupportedOperation(
+            "input actions are not supported by the fantoccini client".to_owned(),
+        ))
+    }
+
+    async fn disconnect(&mut self) -> Result<(), ScrapeError> {
+        self.close_window().await.map_err(ScrapeError::WebdriverCommandError)?;
+        self.close().await.map_err(ScrapeError::WebdriverCommandError)
+    }
+}
+
+/// WebDriver session opened by `Scrapman`. Commands are issued with the fantoccini client, the commands fantoccini
+/// does not support, e.g. the input actions, are sent to the WebDriver server directly
+#[derive(Clone)]
+pub struct WebDriverClient {
+    client: Client,
+    webdriver_url: String,
+    http: hyper::Client<HttpsConnector<HttpConnector>>,
+}
+
+impl WebDriverClient {
+    pub fn new<T: Into<String>>(client: Client, webdriver_url: T) -> Self {
+        WebDriverClient {
+            client,
+            webdriver_url: webdriver_url.into(),
+            http: hyper::Client::builder().build(HttpsConnector::new()),
+        }
+    }
+
+    pub fn client(&mut self) -> &mut Client {
+        &mut self.client
+    }
+}
+
+#[async_trait]
+impl ScrapeClient for WebDriverClient {
+    async fn goto(&mut self, url: &str) -> Result<(), ScrapeError> {
+        ScrapeClient::goto(&mut self.client, url).await
+    }
+
+    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+        ScrapeClient::find_all(&mut self.client, search).await
+    }
+
+    async fn execute(&mut self, script: &str, args: Vec<JsonValue>) -> Result<JsonValue, ScrapeError> {
+        ScrapeClient::execute(&mut self.client, script, args).await
+    }
+
+    async fn execute_async(&mut self, script: &str, args: Vec<JsonValue>) -> Result<JsonValue, ScrapeError> {
+        ScrapeClient::execute_async(&mut self.client, script, args).await
+    }
+
+    async fn screenshot(&mut self) -> Result<Vec<u8>, ScrapeError> {
+        ScrapeClient::screenshot(&mut self.client).await
+    }
+
+    async fn source(&mut self) -> Result<String, ScrapeError> {
+        ScrapeClient::source(&mut self.client).await
+    }
+
+    async fn current_url(&mut self) -> Result<String, ScrapeError> {
+        ScrapeClient::current_url(&mut self.client).await
+    }
+
+    /// Actions are posted to the session "actions" endpoint, W3C error responses are returned as command errors
+    async fn perform_actions(&mut self, actions: Vec<JsonValue>) -> Result<(), ScrapeError> {
+        let session = self
+            .client
+            .session_id()
+            .await
+            .map_err(ScrapeError::WebdriverCommandError)?
+            .ok_or_else(|| command_error(json!("session is not opened")))?;
+
+        let url = format!(
+            "{}/session/{}/actions",
+            self.webdriver_url.trim_end_matches('/'),
+            session
+        );
+        let request = Request::post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(json!({ "actions": actions }).to_string()))
+            .map_err(|error| command_error(json!(error.to_string())))?;
+
+        let response = self
+            .http
+            .request(request)
+            .await
+            .map_err(|error| ScrapeError::WebdriverCommandError(CmdError::Failed(error)))?;
+
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(|error| ScrapeError::WebdriverCommandError(CmdError::Failed(error)))?;
+
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let body: JsonValue = serde_json::from_slice(&body).unwrap_or_default();
+        let status = serde_json::from_value::<ErrorStatus>(body["value"]["error"].clone()).ok();
+        let error = match (status, body["value"]["message"].as_str().map(str::to_owned)) {
+            (Some(status), Some(message)) => CmdError::Standard(WebDriverError::new(status, message)),
+            _ => CmdError::NotW3C(body),
+        };
+
+        Err(ScrapeError::WebdriverCommandError(error))
+    }
+
+    async fn disconnect(&mut self) -> Result<(), ScrapeError> {
+        ScrapeClient::disconnect(&mut self.client).await
+    }
+}
+
+#[async_trait]
+impl ScrapeElement for Element {
+    async fn text(&mut self) -> Result<String, ScrapeError> {
+        self.text().await.map_err(ScrapeError::WebdriverCommandError)
+    }
+
+    async fn attr(&mut self, name: &str) -> Result<Option<String>, ScrapeError> {
+        self.attr(name).await.map_err(ScrapeError::WebdriverCommandError)
+    }
+
+    async fn find_all(&mut self, search: Locator<'_>) -> Result<Vec<Box<dyn ScrapeElement>>, ScrapeError> {
+        self.find_all(search.into())
+            .await
+            .map(|elements| elements.into_iter().map(boxed).collect())
+            .map_err(ScrapeError::WebdriverCommandError)
+    }
+
+    async fn click(&mut self) -> Result<(), ScrapeError> {
+        // Click consumes the element and returns the client, the element itself remains valid
+        self.clone()
+            .click()
+            .await
+            .map(|_| ())
+            .map_err(ScrapeError::WebdriverCommandError)
+    }
+
+    async fn send_keys(&mut self, text: &str) -> Result<(), ScrapeError> {
+        self.send_keys(text).await.map_err(ScrapeError::WebdriverCommandError)
+    }
+
+    async fn screenshot(&mut self) -> Result<Vec<u8>, ScrapeError> {
+        self.clone()
+            .client()
+            .screenshot_element(self.clone())
+            .await
+            .map_err(ScrapeError::WebdriverCommandError)
+    }
+
+    fn reference(&self) -> Option<JsonValue> {
+        serde_json::to_value(self).ok()
+    }
+
+    fn clone_element(&self) -> Box<dyn ScrapeElement> {
+        Box::new(self.clone())
+    }
+}
+
+fn command_error(error: JsonValue) -> ScrapeError {
+    ScrapeError::WebdriverCommandError(CmdError::NotW3C(error))
+}
+
+/// Errors thrown by the script are not considered webdriver failures
+fn script_error(error: CmdError) -> ScrapeError {
+    match error {
+        CmdError::Standard(ref error) if matches!(error.error_code(), "javascript error" | "script timeout") => {
+            ScrapeError::ScriptError(error.message.to_string())
+        }
+        error => ScrapeError::WebdriverCommandError(error),
+    }
+}